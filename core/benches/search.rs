@@ -5,8 +5,7 @@ use candle_core::Device;
 use candle_nn::VarMap;
 use cozy_chess::Board;
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, SamplingMode};
-use search::{Engine, EngineConfig};
-use uci::commands::GoParams;
+use search::{Engine, EngineConfig, SearchLimits};
 
 const DEPTH: u8 = 15;
 const SAMPLE_SIZE: usize = 10;
@@ -61,7 +60,7 @@ fn bench_positions(c: &mut Criterion) {
                 engine.new_game();
                 engine.set_position(board.clone(), None);
                 black_box(engine.search(
-                    &GoParams {
+                    &SearchLimits {
                         depth: Some(DEPTH),
                         ..Default::default()
                     },
@@ -0,0 +1,534 @@
+use std::io::BufRead;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    mpsc::{self, Receiver, Sender},
+    Arc,
+};
+use std::thread::{self, JoinHandle};
+
+use cozy_chess::{util::parse_uci_move, Board, Color};
+use search::{Engine, EngineConfig};
+use uci::{
+    commands::{GoParams, Score},
+    UciOutput, NULL_MOVE,
+};
+use utils::GameHistory;
+use xboard::{Decoder, XBoardConnection, XBoardInput, XBoardOutput, MATE_SCORE};
+
+use crate::engine::create_engine;
+use crate::grail::{ENGINE_NAME, ENGINE_VERSION};
+use crate::nnue::embedded_nnue;
+use crate::worker::{EngineCommand, EngineWorker};
+
+/// Time per move when the GUI set neither a clock nor a depth limit.
+const DEFAULT_MOVE_TIME: u64 = 5000;
+
+/// The XBoard/CECP (protocol version 2) frontend.
+///
+/// Unlike UCI, the engine keeps track of the game itself: the GUI only sends
+/// moves, and we decide when to think. Searches run on the same `EngineWorker`
+/// as in UCI mode, and its output is translated to XBoard on the way out.
+pub struct XBoard {
+    stop: Arc<AtomicBool>,
+    /// Show thinking output ("post"/"nopost"), read by the output adapter
+    post: Arc<AtomicBool>,
+    /// Play the best move when the running search ends. Cleared to abandon a search.
+    announce: Arc<AtomicBool>,
+    /// One message per finished search: the move we played, if any
+    finished: Receiver<Option<String>>,
+    searching: bool,
+
+    board: Board,
+    /// Positions before the current one, for repetition detection
+    game_history: GameHistory,
+    /// Only track moves, don't play either side
+    force: bool,
+    analyzing: bool,
+    clock: Clock,
+
+    cmd_tx: Sender<EngineCommand>,
+    output: Sender<XBoardOutput>,
+    worker_handle: JoinHandle<()>,
+}
+
+/// Time control set by "level", "st", "sd", "time" and "otim" (milliseconds).
+#[derive(Default)]
+struct Clock {
+    moves_per_control: u64,
+    base: Option<u64>,
+    increment: u64,
+    move_time: Option<u64>,
+    max_depth: Option<u8>,
+    time_left: Option<u64>,
+    opponent_time_left: Option<u64>,
+}
+
+impl XBoard {
+    /// Creates the frontend, spawning the engine worker thread.
+    pub fn new() -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let connection = XBoardConnection::new();
+
+        let config = EngineConfig::default();
+        let nnue = embedded_nnue().expect("Failed to load NNUE model");
+        let engine = create_engine(&config, Some(nnue.evaluator), Arc::clone(&stop));
+
+        Self::with_engine(engine, stop, connection.output_sender())
+    }
+
+    /// Creates the frontend around an engine whose searches are stopped by `stop`.
+    fn with_engine(engine: Engine, stop: Arc<AtomicBool>, output: Sender<XBoardOutput>) -> Self {
+        let post = Arc::new(AtomicBool::new(false));
+        let announce = Arc::new(AtomicBool::new(false));
+        let (cmd_tx, cmd_rx) = mpsc::channel();
+        let (engine_tx, engine_rx) = mpsc::channel();
+        let (finished_tx, finished) = mpsc::channel();
+
+        let worker = EngineWorker::new(engine, cmd_rx, engine_tx, Arc::clone(&stop));
+        let worker_handle = thread::spawn(move || worker.run());
+
+        let adapter = OutputAdapter {
+            output: output.clone(),
+            post: Arc::clone(&post),
+            announce: Arc::clone(&announce),
+            finished: finished_tx,
+        };
+        thread::spawn(move || adapter.run(engine_rx));
+
+        Self {
+            stop,
+            post,
+            announce,
+            finished,
+            searching: false,
+            board: Board::default(),
+            game_history: GameHistory::new(),
+            force: false,
+            analyzing: false,
+            clock: Clock::default(),
+            cmd_tx,
+            output,
+            worker_handle,
+        }
+    }
+
+    /// Runs the XBoard protocol loop until quit.
+    pub fn run(mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let decoder = Decoder::new();
+        let stdin = std::io::stdin();
+
+        for line in stdin.lock().lines() {
+            let line = line?;
+            self.collect_finished();
+            if !self.handle(decoder.decode(line.trim())) {
+                break;
+            }
+        }
+
+        self.shutdown();
+        Ok(())
+    }
+
+    /// Handles a single XBoard command. Returns false if we should quit.
+    fn handle(&mut self, input: XBoardInput) -> bool {
+        match input {
+            XBoardInput::XBoard | XBoardInput::Ignored => {}
+            XBoardInput::ProtoVer(_) => {
+                let features = [
+                    ("ping", "1"),
+                    ("setboard", "1"),
+                    ("usermove", "1"),
+                    ("san", "0"),
+                    ("time", "1"),
+                    ("draw", "0"),
+                    ("sigint", "0"),
+                    ("sigterm", "0"),
+                    ("reuse", "1"),
+                    ("analyze", "1"),
+                    ("colors", "0"),
+                    ("myname", &format!("{} {}", ENGINE_NAME, ENGINE_VERSION)),
+                    ("done", "1"),
+                ];
+                let _ = self.output.send(XBoardOutput::Features(
+                    features
+                        .iter()
+                        .map(|(name, value)| (name.to_string(), value.to_string()))
+                        .collect(),
+                ));
+            }
+            XBoardInput::New => {
+                self.stop_search();
+                self.board = Board::default();
+                self.game_history.clear();
+                self.force = false;
+                self.clock.max_depth = None;
+                let _ = self.cmd_tx.send(EngineCommand::NewGame);
+                self.restart_analysis();
+            }
+            XBoardInput::SetBoard(board) => {
+                self.stop_search();
+                self.board = board;
+                self.game_history.clear();
+                self.restart_analysis();
+            }
+            XBoardInput::Force => {
+                self.force = true;
+                self.stop_search();
+            }
+            XBoardInput::Go => {
+                self.force = false;
+                self.stop_search();
+                self.start_search(true);
+            }
+            XBoardInput::UserMove(mv) => {
+                self.stop_search();
+                if let Err(reason) = self.play_move(&mv) {
+                    let _ = self.output.send(XBoardOutput::IllegalMove { mv, reason });
+                } else if self.analyzing {
+                    self.start_search(false);
+                } else if !self.force {
+                    self.start_search(true);
+                }
+            }
+            XBoardInput::MoveNow => {
+                self.stop.store(true, Ordering::Relaxed);
+            }
+            XBoardInput::Analyze => {
+                self.stop_search();
+                self.analyzing = true;
+                self.start_search(false);
+            }
+            XBoardInput::Exit => {
+                if self.analyzing {
+                    self.stop_search();
+                    self.analyzing = false;
+                }
+            }
+            XBoardInput::Result(_) => {
+                self.stop_search();
+            }
+            XBoardInput::Level {
+                moves_per_control,
+                base,
+                increment,
+            } => {
+                self.clock.moves_per_control = moves_per_control;
+                self.clock.base = Some(base);
+                self.clock.increment = increment;
+                self.clock.move_time = None;
+            }
+            XBoardInput::MoveTime(move_time) => self.clock.move_time = Some(move_time),
+            XBoardInput::Depth(depth) => self.clock.max_depth = Some(depth),
+            XBoardInput::Time(time) => self.clock.time_left = Some(time),
+            XBoardInput::OTime(time) => self.clock.opponent_time_left = Some(time),
+            XBoardInput::Post => self.post.store(true, Ordering::Relaxed),
+            XBoardInput::NoPost => self.post.store(false, Ordering::Relaxed),
+            XBoardInput::Ping(n) => {
+                let _ = self.output.send(XBoardOutput::Pong(n));
+            }
+            XBoardInput::Quit => return false,
+            XBoardInput::Invalid { reason, command } => {
+                let _ = self.output.send(XBoardOutput::Error {
+                    kind: reason,
+                    command,
+                });
+            }
+            XBoardInput::Unknown(command) => {
+                let _ = self.output.send(XBoardOutput::Error {
+                    kind: "unknown command".to_string(),
+                    command,
+                });
+            }
+        }
+        true
+    }
+
+    /// Searches the current position, either to play a move or to analyze.
+    fn start_search(&mut self, play: bool) {
+        let _ = self.cmd_tx.send(EngineCommand::SetPosition {
+            board: self.board.clone(),
+            history: self.game_history.clone(),
+        });
+
+        self.announce.store(play, Ordering::Relaxed);
+        self.stop.store(false, Ordering::Relaxed);
+        let params = go_params(&self.clock, &self.board, self.analyzing);
+        let _ = self.cmd_tx.send(EngineCommand::Go(params));
+        self.searching = true;
+    }
+
+    /// Ends the running search without playing its move, and waits for it to finish.
+    fn stop_search(&mut self) {
+        if !self.searching {
+            return;
+        }
+
+        self.announce.store(false, Ordering::Relaxed);
+        self.stop.store(true, Ordering::Relaxed);
+
+        // The move may already have been sent before we got here
+        if let Ok(played) = self.finished.recv() {
+            self.searching = false;
+            self.apply_engine_move(played);
+        }
+    }
+
+    fn restart_analysis(&mut self) {
+        if self.analyzing {
+            self.start_search(false);
+        }
+    }
+
+    /// Picks up the moves we played in searches that have finished.
+    fn collect_finished(&mut self) {
+        while let Ok(played) = self.finished.try_recv() {
+            self.searching = false;
+            self.apply_engine_move(played);
+        }
+    }
+
+    fn apply_engine_move(&mut self, played: Option<String>) {
+        if let Some(mv) = played {
+            let _ = self.play_move(&mv);
+        }
+    }
+
+    fn play_move(&mut self, mv: &str) -> Result<(), String> {
+        let parsed = parse_uci_move(&self.board, mv).map_err(|_| "invalid move".to_string())?;
+        if !self.board.is_legal(parsed) {
+            return Err("illegal move".to_string());
+        }
+
+        self.game_history.play(&mut self.board, parsed);
+        Ok(())
+    }
+
+    fn shutdown(mut self) {
+        self.stop_search();
+        let _ = self.cmd_tx.send(EngineCommand::Quit);
+        let _ = self.worker_handle.join();
+    }
+}
+
+/// Search limits for the engine's turn (or for analysis) under the XBoard clock.
+///
+/// XBoard only sends our remaining time, so the opponent's defaults to ours when
+/// "otim" is missing, and the moves left in a "level" control follow from the move number.
+fn go_params(clock: &Clock, board: &Board, analyzing: bool) -> GoParams {
+    if analyzing {
+        return GoParams {
+            infinite: true,
+            ..Default::default()
+        };
+    }
+
+    let mut params = GoParams {
+        depth: clock.max_depth,
+        ..Default::default()
+    };
+
+    if let Some(move_time) = clock.move_time {
+        params.move_time = Some(move_time);
+        return params;
+    }
+
+    let Some(ours) = clock.time_left.or(clock.base) else {
+        if clock.max_depth.is_none() {
+            params.move_time = Some(DEFAULT_MOVE_TIME);
+        }
+        return params;
+    };
+    let theirs = clock.opponent_time_left.or(clock.base).unwrap_or(ours);
+    let increment = Some(clock.increment);
+
+    match board.side_to_move() {
+        Color::White => {
+            params.wtime = Some(ours);
+            params.btime = Some(theirs);
+        }
+        Color::Black => {
+            params.wtime = Some(theirs);
+            params.btime = Some(ours);
+        }
+    }
+    params.winc = increment;
+    params.binc = increment;
+
+    if clock.moves_per_control > 0 {
+        let played = (board.fullmove_number() as u64 - 1) % clock.moves_per_control;
+        params.moves_to_go = Some(clock.moves_per_control - played);
+    }
+
+    params
+}
+
+/// Translates the worker's UCI output to XBoard.
+struct OutputAdapter {
+    output: Sender<XBoardOutput>,
+    post: Arc<AtomicBool>,
+    announce: Arc<AtomicBool>,
+    finished: Sender<Option<String>>,
+}
+
+impl OutputAdapter {
+    fn run(self, engine_rx: Receiver<UciOutput>) {
+        while let Ok(message) = engine_rx.recv() {
+            match message {
+                UciOutput::BestMove { best_move, .. } => {
+                    let play = self.announce.swap(false, Ordering::Relaxed);
+
+                    if play && best_move != NULL_MOVE {
+                        // Report the move before the GUI can answer it
+                        let _ = self.finished.send(Some(best_move.clone()));
+                        let _ = self.output.send(XBoardOutput::Move(best_move));
+                    } else {
+                        let _ = self.finished.send(None);
+                    }
+                }
+                // XBoard has no bounds or MultiPV, so only exact scores of the best line are posted
+                UciOutput::Info(info)
+                    if self.post.load(Ordering::Relaxed)
+                        && info.bound.is_none()
+                        && info.multi_pv.unwrap_or(1) == 1 =>
+                {
+                    let _ = self.output.send(XBoardOutput::Thinking {
+                        depth: info.depth,
+                        score: match info.score {
+                            Score::Centipawns(cp) => cp as i32,
+                            Score::Mate(moves) if moves > 0 => MATE_SCORE + moves as i32,
+                            Score::Mate(moves) => -MATE_SCORE + moves as i32,
+                        },
+                        time: info.time / 10,
+                        nodes: info.nodes,
+                        pv: info.pv,
+                    });
+                }
+                UciOutput::InfoString(message) => {
+                    let _ = self.output.send(XBoardOutput::Comment(message));
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn board(fen: &str) -> Board {
+        fen.parse().unwrap()
+    }
+
+    #[test]
+    fn test_go_params_without_clock() {
+        let start = Board::default();
+
+        let params = go_params(&Clock::default(), &start, false);
+        assert_eq!(params.move_time, Some(DEFAULT_MOVE_TIME));
+
+        // "sd" alone searches to that depth without a time limit
+        let clock = Clock {
+            max_depth: Some(5),
+            ..Default::default()
+        };
+        let params = go_params(&clock, &start, false);
+        assert_eq!(params.depth, Some(5));
+        assert_eq!(params.move_time, None);
+        assert_eq!(params.wtime, None);
+
+        // "st" takes precedence over the clock
+        let clock = Clock {
+            move_time: Some(2000),
+            max_depth: Some(5),
+            time_left: Some(60_000),
+            ..Default::default()
+        };
+        let params = go_params(&clock, &start, false);
+        assert_eq!(params.move_time, Some(2000));
+        assert_eq!(params.depth, Some(5));
+        assert_eq!(params.wtime, None);
+
+        assert!(go_params(&clock, &start, true).infinite);
+    }
+
+    #[test]
+    fn test_go_params_with_clock() {
+        // "level 40 5 2", then "time" and "otim" with black to move on move 12
+        let clock = Clock {
+            moves_per_control: 40,
+            base: Some(300_000),
+            increment: 2000,
+            time_left: Some(120_000),
+            opponent_time_left: Some(90_000),
+            ..Default::default()
+        };
+        let black = board("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 12");
+        let params = go_params(&clock, &black, false);
+        assert_eq!(params.btime, Some(120_000));
+        assert_eq!(params.wtime, Some(90_000));
+        assert_eq!((params.winc, params.binc), (Some(2000), Some(2000)));
+        assert_eq!(params.moves_to_go, Some(29));
+
+        // A new control starts after move 40
+        let white = board("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 41");
+        let params = go_params(&clock, &white, false);
+        assert_eq!(params.wtime, Some(120_000));
+        assert_eq!(params.moves_to_go, Some(40));
+
+        // Before any "time" or "otim", both sides have the base time; incremental has no moves to go
+        let clock = Clock {
+            base: Some(300_000),
+            ..Default::default()
+        };
+        let params = go_params(&clock, &white, false);
+        assert_eq!((params.wtime, params.btime), (Some(300_000), Some(300_000)));
+        assert_eq!(params.moves_to_go, None);
+        assert_eq!(params.move_time, None);
+    }
+
+    /// Waits for the engine's move and plays it, like `collect_finished` does.
+    fn wait_for_engine_move(xboard: &mut XBoard) -> String {
+        let played = xboard
+            .finished
+            .recv_timeout(Duration::from_secs(60))
+            .expect("search did not finish");
+        xboard.searching = false;
+        xboard.apply_engine_move(played.clone());
+        played.expect("engine did not play a move")
+    }
+
+    #[test]
+    fn test_user_and_engine_moves_stay_in_sync() {
+        let mut config = EngineConfig::default();
+        config.hash_size.value = 1;
+        let stop = Arc::new(AtomicBool::new(false));
+        let engine = create_engine(&config, None, Arc::clone(&stop));
+        let (output, _output_rx) = mpsc::channel();
+        let mut xboard = XBoard::with_engine(engine, stop, output);
+
+        xboard.handle(XBoardInput::New);
+        xboard.handle(XBoardInput::Depth(3));
+
+        let mut expected = Board::default();
+        let mut history = GameHistory::new();
+
+        for user_move in ["g1f3", "b1c3"] {
+            xboard.handle(XBoardInput::UserMove(user_move.to_string()));
+            let reply = wait_for_engine_move(&mut xboard);
+
+            for mv in [user_move, reply.as_str()] {
+                let mv = parse_uci_move(&expected, mv).unwrap();
+                history.play(&mut expected, mv);
+            }
+
+            assert_eq!(xboard.board, expected);
+            assert_eq!(xboard.game_history.len(), history.len());
+            assert_eq!(xboard.board.side_to_move(), Color::White);
+        }
+
+        xboard.shutdown();
+    }
+}
@@ -0,0 +1,92 @@
+use std::fmt::Write;
+
+use cozy_chess::{Board, Color, File, Rank, Square};
+use uci::move_to_uci;
+
+/// Describes the position held by the engine (the "d" command), to check
+/// that it matches what the GUI thinks it sent.
+pub fn describe_position(board: &Board, history_len: usize, chess960: bool) -> String {
+    let mut out = String::new();
+    let separator = " +---+---+---+---+---+---+---+---+";
+
+    for &rank in Rank::ALL.iter().rev() {
+        let _ = writeln!(out, "{}", separator);
+        out.push(' ');
+        for &file in &File::ALL {
+            let _ = write!(out, "| {} ", piece_char(board, Square::new(file, rank)));
+        }
+        let _ = writeln!(out, "| {}", rank as u8 + 1);
+    }
+    let _ = writeln!(out, "{}", separator);
+    let _ = writeln!(out, "   a   b   c   d   e   f   g   h");
+    let _ = writeln!(out);
+
+    let fen = if chess960 {
+        format!("{:#}", board)
+    } else {
+        format!("{}", board)
+    };
+    let fields: Vec<&str> = fen.split(' ').collect();
+
+    let side = match board.side_to_move() {
+        Color::White => "white",
+        Color::Black => "black",
+    };
+
+    let mut moves = Vec::new();
+    board.generate_moves(|piece_moves| {
+        moves.extend(
+            piece_moves
+                .into_iter()
+                .map(|mv| move_to_uci(board, mv, chess960)),
+        );
+        false
+    });
+    moves.sort();
+
+    let _ = writeln!(out, "Fen: {}", fen);
+    let _ = writeln!(out, "Key: {:016X}", board.hash());
+    let _ = writeln!(out, "Side to move: {}", side);
+    let _ = writeln!(out, "Castling: {}", fields[2]);
+    let _ = writeln!(out, "En passant: {}", fields[3]);
+    let _ = writeln!(out, "Game history: {} positions", history_len);
+    let _ = write!(out, "Legal moves ({}): {}", moves.len(), moves.join(" "));
+
+    out
+}
+
+fn piece_char(board: &Board, square: Square) -> char {
+    let Some(piece) = board.piece_on(square) else {
+        return ' ';
+    };
+
+    let symbol: char = piece.into();
+    match board.color_on(square) {
+        Some(Color::White) => symbol.to_ascii_uppercase(),
+        _ => symbol,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_position() {
+        let board: Board = "r3k2r/8/8/3pP3/8/8/8/R3K2R w KQkq d6 0 2".parse().unwrap();
+        let text = describe_position(&board, 3, false);
+
+        assert!(text.contains(" | r |   |   |   | k |   |   | r | 8"));
+        assert!(text.contains("Fen: r3k2r/8/8/3pP3/8/8/8/R3K2R w KQkq d6 0 2"));
+        assert!(text.contains("Side to move: white"));
+        assert!(text.contains("Castling: KQkq"));
+        assert!(text.contains("En passant: d6"));
+        assert!(text.contains("Game history: 3 positions"));
+        assert!(text.contains("e5d6"));
+        assert!(text.contains("e1g1"));
+
+        let shredder = describe_position(&board, 3, true);
+        assert!(shredder.contains("Castling: HAha"));
+        assert!(shredder.contains("e1h1"));
+    }
+}
@@ -6,14 +6,16 @@ use std::sync::{
 };
 use std::thread::{self, JoinHandle};
 
-use search::EngineConfig;
+use search::{EngineConfig, Pondering};
 use uci::{Decoder, UciConnection, UciInput, UciOutput};
 
 use crate::engine::create_engine;
+use crate::nnue::{embedded_nnue, nnue_from_file};
+use crate::uci_adapter::uci_option;
 use crate::worker::{EngineCommand, EngineWorker};
 
-const ENGINE_NAME: &str = "Grail";
-const ENGINE_VERSION: &str = env!("CARGO_PKG_VERSION");
+pub(crate) const ENGINE_NAME: &str = "Grail";
+pub(crate) const ENGINE_VERSION: &str = env!("CARGO_PKG_VERSION");
 const ENGINE_AUTHOR: &str = "Jørgen Hanssen";
 
 /// The main UCI application.
@@ -23,6 +25,11 @@ const ENGINE_AUTHOR: &str = "Jørgen Hanssen";
 pub struct Grail {
     config: EngineConfig,
     stop: Arc<AtomicBool>,
+    pondering: Pondering,
+    /// UCI debug mode - send extra diagnostics as `info string`
+    debug: bool,
+    /// Describes the active NNUE (source and hash), reported after `uci`
+    nnue_info: String,
     cmd_tx: Sender<EngineCommand>,
     output: Sender<UciOutput>,
     worker_handle: JoinHandle<()>,
@@ -38,25 +45,37 @@ impl Grail {
         let output = uci.output_sender();
 
         let config = EngineConfig::default();
-        let engine = create_engine(&config, Arc::clone(&stop));
+        let nnue = embedded_nnue().expect("Failed to load NNUE model");
+        let nnue_info = nnue.describe("embedded");
+        let engine = create_engine(&config, Some(nnue.evaluator), Arc::clone(&stop));
+        let pondering = engine.pondering();
 
-        let worker = EngineWorker::new(engine, cmd_rx, output.clone());
+        let worker = EngineWorker::new(engine, cmd_rx, output.clone(), Arc::clone(&stop));
         let worker_handle = thread::spawn(move || worker.run());
 
         Self {
             config,
             stop,
+            pondering,
+            debug: false,
+            nnue_info,
             cmd_tx,
             output,
             worker_handle,
         }
     }
 
-    /// Runs the UCI protocol loop until quit.
-    pub fn run(mut self) -> Result<(), Box<dyn std::error::Error>> {
+    /// Runs the UCI protocol loop until quit, starting with the line
+    /// that was read to detect the protocol.
+    pub fn run(mut self, first_line: &str) -> Result<(), Box<dyn std::error::Error>> {
         let decoder = Decoder::new();
         let stdin = std::io::stdin();
 
+        if !self.handle(decoder.decode(first_line.trim())) {
+            self.shutdown();
+            return Ok(());
+        }
+
         for line in stdin.lock().lines() {
             let line = line?;
             let input = decoder.decode(line.trim());
@@ -80,17 +99,28 @@ impl Grail {
                 let _ = self
                     .output
                     .send(UciOutput::IdAuthor(ENGINE_AUTHOR.to_string()));
-                let _ = self.config.to_uci(&self.output);
+                for (option, value) in self.config.options() {
+                    let _ = self.output.send(uci_option(&option, &value));
+                }
                 let _ = self.output.send(UciOutput::UciOk);
+                let _ = self
+                    .output
+                    .send(UciOutput::InfoString(self.nnue_info.clone()));
             }
             UciInput::IsReady => {
                 let _ = self.output.send(UciOutput::ReadyOk);
             }
-            // TODO: Implement debug mode - send extra info via `info string` when enabled
-            UciInput::Debug(_enabled) => {}
+            UciInput::Debug(enabled) => {
+                self.debug = enabled;
+                let _ = self.cmd_tx.send(EngineCommand::Debug(enabled));
+            }
             UciInput::SetOption { name, value } => {
-                if let Err(e) = self.config.update_from_uci(&name, &value) {
+                if let Err(e) = self.config.set_option(&name, &value) {
                     let _ = self.output.send(UciOutput::InfoString(e.to_string()));
+                } else if self.config.is_button(&name) {
+                    let _ = self.cmd_tx.send(EngineCommand::PressButton(name));
+                } else if self.config.eval_file.option_name() == Some(name.as_str()) {
+                    self.load_eval_file();
                 } else {
                     let _ = self
                         .cmd_tx
@@ -104,26 +134,112 @@ impl Grail {
                 board,
                 game_history,
             } => {
+                self.send_debug(|| {
+                    let fen = if self.config.chess960.value {
+                        format!("{:#}", board)
+                    } else {
+                        format!("{}", board)
+                    };
+                    format!(
+                        "position fen {} ({} previous positions)",
+                        fen,
+                        game_history.len()
+                    )
+                });
                 let _ = self.cmd_tx.send(EngineCommand::SetPosition {
                     board,
                     history: game_history,
                 });
             }
             UciInput::Go(params) => {
+                // Set here rather than in the worker so an early ponderhit or stop is not lost
+                if params.ponder {
+                    self.pondering.start();
+                } else {
+                    self.pondering.end();
+                }
                 self.stop.store(false, Ordering::Relaxed);
                 let _ = self.cmd_tx.send(EngineCommand::Go(params));
             }
+            UciInput::PonderHit => {
+                self.pondering.end();
+            }
             UciInput::Stop => {
                 self.stop.store(true, Ordering::Relaxed);
+                self.pondering.end();
+            }
+            UciInput::Display => {
+                let _ = self.cmd_tx.send(EngineCommand::Display);
+            }
+            UciInput::Eval => {
+                let _ = self.cmd_tx.send(EngineCommand::Eval);
+            }
+            UciInput::Perft { depth } => {
+                let _ = self.cmd_tx.send(EngineCommand::Perft(depth));
+            }
+            UciInput::Bench { depth } => {
+                self.stop.store(false, Ordering::Relaxed);
+                let _ = self.cmd_tx.send(EngineCommand::Bench(depth));
             }
             UciInput::Quit => return false,
-            UciInput::Unknown(_) => {} // Ignore unknown commands per UCI spec
+            // Keep the previous state and tell the GUI what was wrong
+            UciInput::Invalid { reason } => {
+                let _ = self.output.send(UciOutput::InfoString(reason));
+            }
+            // Ignore unknown commands per UCI spec, but report them when debugging
+            UciInput::Unknown(command) => {
+                self.send_debug(|| format!("unknown command: {}", command));
+            }
         }
         true
     }
 
+    /// Loads the network named by the `EvalFile` option and hands it to the engine.
+    /// Falls back to the embedded network if the path is empty or the file can't be used.
+    fn load_eval_file(&mut self) {
+        let path = self.config.eval_file.value.clone();
+
+        let nnue = if path.is_empty() {
+            embedded_nnue().map(|nnue| (nnue, "embedded".to_string()))
+        } else {
+            match nnue_from_file(&path) {
+                Ok(nnue) => Ok((nnue, path)),
+                Err(e) => {
+                    let _ = self.output.send(UciOutput::InfoString(format!(
+                        "failed to load EvalFile {}: {}, using embedded network",
+                        path, e
+                    )));
+                    embedded_nnue().map(|nnue| (nnue, "embedded".to_string()))
+                }
+            }
+        };
+
+        match nnue {
+            Ok((nnue, source)) => {
+                self.nnue_info = nnue.describe(&source);
+                let _ = self
+                    .output
+                    .send(UciOutput::InfoString(self.nnue_info.clone()));
+                let _ = self.cmd_tx.send(EngineCommand::SetNnue(nnue.evaluator));
+            }
+            Err(e) => {
+                let _ = self
+                    .output
+                    .send(UciOutput::InfoString(format!("failed to load NNUE: {}", e)));
+            }
+        }
+    }
+
+    /// Sends an `info string` diagnostic if debug mode is on.
+    fn send_debug(&self, message: impl FnOnce() -> String) {
+        if self.debug {
+            let _ = self.output.send(UciOutput::InfoString(message()));
+        }
+    }
+
     fn shutdown(self) {
         self.stop.store(true, Ordering::Relaxed);
+        self.pondering.end();
         let _ = self.cmd_tx.send(EngineCommand::Quit);
         let _ = self.worker_handle.join();
     }
@@ -1,12 +1,50 @@
+mod bench;
+mod cecp;
+mod display;
 mod engine;
 mod grail;
 mod nnue;
+mod uci_adapter;
 mod worker;
 
 use std::error::Error;
+use std::io::BufRead;
+use std::sync::{atomic::AtomicBool, Arc};
 
+use bench::run_bench;
+use cecp::XBoard;
 use grail::Grail;
 
 fn main() -> Result<(), Box<dyn Error>> {
-    Grail::new().run()
+    let args: Vec<String> = std::env::args().collect();
+
+    // "grail bench [depth]" runs the bench suite instead of the UCI loop
+    if args.get(1).map(String::as_str) == Some("bench") {
+        let depth = match args.get(2) {
+            Some(depth) => Some(
+                depth
+                    .parse()
+                    .map_err(|_| format!("invalid bench depth '{}'", depth))?,
+            ),
+            None => None,
+        };
+
+        let stop = Arc::new(AtomicBool::new(false));
+        if let Some(result) = run_bench(depth, stop, |line| eprintln!("{}", line))? {
+            println!("{}", result);
+        }
+        return Ok(());
+    }
+
+    // The first command tells which protocol the GUI speaks
+    let mut first_line = String::new();
+    if std::io::stdin().lock().read_line(&mut first_line)? == 0 {
+        return Ok(());
+    }
+
+    if first_line.trim() == "xboard" {
+        XBoard::new().run()
+    } else {
+        Grail::new().run(&first_line)
+    }
 }
@@ -1,23 +1,66 @@
-use candle_core::safetensors::SliceSafetensors;
-use candle_core::Device;
-use candle_nn::VarMap;
+use std::fmt;
+use std::path::Path;
+
 use evaluation::NNUE;
 
-pub fn resolve_nnue() -> Result<Box<dyn NNUE>, Box<dyn std::error::Error>> {
-    static NNUE_BYTES: &[u8] = include_bytes!("../../nnue/model.safetensors");
+static EMBEDDED_NNUE: &[u8] = include_bytes!("../../nnue/model.safetensors");
+
+/// An NNUE network ready for the engine, with a hash of the weights it was loaded from.
+pub struct LoadedNnue {
+    pub evaluator: Box<dyn NNUE>,
+    /// FNV-1a hash of the safetensors file, so users can tell which net is active
+    pub hash: u64,
+}
 
-    let varmap = VarMap::new();
-    let mut nnue = nnue::Evaluator::new(&varmap, &Device::Cpu);
+impl LoadedNnue {
+    /// Describes the net for `info string` output, e.g. `NNUE embedded (hash 0123456789abcdef)`.
+    pub fn describe(&self, source: &str) -> String {
+        format!("NNUE {} (hash {:016x})", source, self.hash)
+    }
+}
 
-    let st = SliceSafetensors::new(NNUE_BYTES)?;
-    let mut tensor_data = varmap.data().lock().unwrap();
+#[derive(Debug)]
+pub enum NnueLoadError {
+    Io(std::io::Error),
+    Weights(candle_core::Error),
+}
 
-    for (name, var) in tensor_data.iter_mut() {
-        let tensor = st.load(name, var.device())?;
-        var.set(&tensor)?;
+impl fmt::Display for NnueLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NnueLoadError::Io(e) => write!(f, "could not read file: {}", e),
+            NnueLoadError::Weights(e) => write!(f, "incompatible network: {}", e),
+        }
     }
+}
+
+impl std::error::Error for NnueLoadError {}
+
+/// Loads the network compiled into the binary.
+pub fn embedded_nnue() -> Result<LoadedNnue, NnueLoadError> {
+    load_nnue(EMBEDDED_NNUE)
+}
+
+/// Loads a network from a safetensors file (the `EvalFile` option).
+pub fn nnue_from_file(path: impl AsRef<Path>) -> Result<LoadedNnue, NnueLoadError> {
+    let bytes = std::fs::read(path).map_err(NnueLoadError::Io)?;
+    load_nnue(&bytes)
+}
+
+fn load_nnue(bytes: &[u8]) -> Result<LoadedNnue, NnueLoadError> {
+    let evaluator = nnue::Evaluator::from_safetensors(bytes).map_err(NnueLoadError::Weights)?;
+
+    Ok(LoadedNnue {
+        evaluator: Box::new(evaluator),
+        hash: fnv1a(bytes),
+    })
+}
 
-    nnue.enable_nnue();
+fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
 
-    Ok(Box::new(nnue))
+    bytes.iter().fold(OFFSET_BASIS, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(PRIME)
+    })
 }
@@ -0,0 +1,123 @@
+use std::sync::mpsc::Sender;
+
+use cozy_chess::{util::parse_uci_move, Board, Move};
+use search::{
+    EngineOption, OptionType, ScoreBound, SearchInfo, SearchLimits, SearchObserver, SearchScore,
+    MAX_DEPTH,
+};
+use uci::{
+    commands::{self, GoParams, Info, Score},
+    move_to_uci, pv_to_uci, UciOptionType, UciOutput,
+};
+
+/// Announces an engine option and its current value as a UCI `option` line.
+pub fn uci_option(option: &EngineOption, value: &str) -> UciOutput {
+    let option_type = match option.option_type {
+        OptionType::Spin { min, max } => UciOptionType::Spin { min, max },
+        OptionType::Check => UciOptionType::Check,
+        OptionType::Button => UciOptionType::Button,
+        OptionType::String => UciOptionType::String,
+        OptionType::Combo { options } => UciOptionType::Combo { options },
+    };
+
+    UciOutput::Option(option_type.to_uci(option.name, &value))
+}
+
+/// Converts the parameters of a "go" command to search limits.
+/// The depth is capped at what the search supports, and search moves that
+/// don't parse in the given position are dropped.
+pub fn search_limits(params: &GoParams, board: &Board) -> SearchLimits {
+    SearchLimits {
+        infinite: params.infinite,
+        white_time: params.wtime,
+        black_time: params.btime,
+        white_increment: params.winc,
+        black_increment: params.binc,
+        moves_to_go: params.moves_to_go,
+        depth: params.depth.map(|depth| depth.min(MAX_DEPTH as u8)),
+        nodes: params.nodes,
+        mate: params.mate,
+        move_time: params.move_time,
+        search_moves: params
+            .search_moves
+            .iter()
+            .filter_map(|mv| parse_uci_move(board, mv).ok())
+            .collect(),
+        ponder: params.ponder,
+    }
+}
+
+/// Reports search progress as UCI `info` lines.
+pub struct UciObserver<'a> {
+    output: &'a Sender<UciOutput>,
+    /// Root position, to encode moves
+    board: Board,
+    /// Encode castling as king-captures-rook (UCI_Chess960)
+    chess960: bool,
+}
+
+impl<'a> UciObserver<'a> {
+    pub fn new(output: &'a Sender<UciOutput>, board: Board, chess960: bool) -> Self {
+        Self {
+            output,
+            board,
+            chess960,
+        }
+    }
+}
+
+impl SearchObserver for UciObserver<'_> {
+    fn on_info(&self, info: &SearchInfo) {
+        let _ = self.output.send(UciOutput::Info(Info {
+            depth: info.depth,
+            sel_depth: info.sel_depth,
+            multi_pv: (info.multi_pv > 0).then_some(info.multi_pv as u16),
+            nodes: info.nodes,
+            nodes_per_second: info.nodes_per_second,
+            hashfull: info.hashfull,
+            time: info.time.as_millis() as u32,
+            pv: pv_to_uci(&self.board, &info.pv, self.chess960),
+            score: match info.score {
+                SearchScore::Centipawns(cp) => Score::Centipawns(cp),
+                SearchScore::Mate(moves) => Score::Mate(moves),
+            },
+            bound: info.bound.map(|bound| match bound {
+                ScoreBound::Lower => commands::ScoreBound::Lower,
+                ScoreBound::Upper => commands::ScoreBound::Upper,
+            }),
+            wdl: info.wdl.map(|wdl| commands::Wdl {
+                win: wdl.win,
+                draw: wdl.draw,
+                loss: wdl.loss,
+            }),
+        }));
+    }
+
+    fn on_current_move(&self, depth: u8, mv: Move, number: u16) {
+        let _ = self.output.send(UciOutput::CurrMove {
+            depth,
+            current_move: move_to_uci(&self.board, mv, self.chess960),
+            number,
+        });
+    }
+
+    fn on_message(&self, message: &str) {
+        let _ = self.output.send(UciOutput::InfoString(message.to_string()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_limits_cap_depth() {
+        let params = GoParams {
+            depth: Some(u8::MAX),
+            ..Default::default()
+        };
+
+        let limits = search_limits(&params, &Board::default());
+        assert_eq!(limits.depth, Some(MAX_DEPTH as u8));
+    }
+}
@@ -1,21 +1,41 @@
-use std::sync::mpsc::{Receiver, Sender};
+use std::sync::{
+    atomic::AtomicBool,
+    mpsc::{Receiver, Sender},
+    Arc,
+};
 
-use ahash::AHashSet;
 use cozy_chess::Board;
+use evaluation::NNUE;
 use search::{Engine, EngineConfig};
 use uci::{move_to_uci, UciOutput, NULL_MOVE};
+use utils::{perft_divide, GameHistory};
+
+use crate::bench::run_bench;
+use crate::display::describe_position;
+use crate::uci_adapter::{search_limits, UciObserver};
 
 /// Commands sent from the UCI thread to the engine worker.
 pub enum EngineCommand {
     /// Update engine configuration.
     Configure(Box<EngineConfig>),
+    /// Perform the action of a button option (e.g. "Clear Hash").
+    PressButton(String),
+    /// Replace the neural network (loaded from the "EvalFile" option).
+    SetNnue(Box<dyn NNUE>),
+    /// Toggle UCI debug mode (extra `info string` diagnostics).
+    Debug(bool),
     /// Reset engine state for a new game.
     NewGame,
     /// Set the position to search from.
-    SetPosition {
-        board: Board,
-        history: AHashSet<u64>,
-    },
+    SetPosition { board: Board, history: GameHistory },
+    /// Print the position held by the engine.
+    Display,
+    /// Print the evaluation breakdown of the current position.
+    Eval,
+    /// Count the move tree of the current position to the given depth.
+    Perft(u8),
+    /// Run the bench suite to the given depth (or the default one).
+    Bench(Option<u8>),
     /// Start searching with the given parameters.
     Go(uci::commands::GoParams),
     /// Shut down the worker thread.
@@ -30,11 +50,26 @@ pub struct EngineWorker {
     engine: Engine,
     rx: Receiver<EngineCommand>,
     output: Sender<UciOutput>,
+    /// Stop flag shared with the engine, also ends a running bench
+    stop: Arc<AtomicBool>,
+    /// Encode castling as king-captures-rook (UCI_Chess960)
+    chess960: bool,
 }
 
 impl EngineWorker {
-    pub fn new(engine: Engine, rx: Receiver<EngineCommand>, output: Sender<UciOutput>) -> Self {
-        Self { engine, rx, output }
+    pub fn new(
+        engine: Engine,
+        rx: Receiver<EngineCommand>,
+        output: Sender<UciOutput>,
+        stop: Arc<AtomicBool>,
+    ) -> Self {
+        Self {
+            engine,
+            rx,
+            output,
+            stop,
+            chess960: false,
+        }
     }
 
     /// Main loop: process commands until Quit is received.
@@ -42,14 +77,63 @@ impl EngineWorker {
         while let Ok(cmd) = self.rx.recv() {
             match cmd {
                 EngineCommand::Go(params) => {
-                    let result = self.engine.search(&params, Some(&self.output));
+                    let board = self.engine.board().clone();
+                    let limits = search_limits(&params, &board);
+                    let observer = UciObserver::new(&self.output, board, self.chess960);
+                    let result = self.engine.search(&limits, Some(&observer));
 
                     // UCI requires bestmove for every "go" command, even in checkmate positions
-                    let uci_move = result
-                        .map(|(mv, _)| move_to_uci(self.engine.board(), mv))
+                    let best_move = result.map(|(mv, _)| mv);
+                    let uci_move = best_move
+                        .map(|mv| move_to_uci(self.engine.board(), mv, self.chess960))
                         .unwrap_or_else(|| NULL_MOVE.to_string());
 
-                    let _ = self.output.send(UciOutput::BestMove(uci_move));
+                    // The reply must be encoded from the position after our move
+                    let ponder = best_move.zip(self.engine.ponder_move()).map(|(mv, reply)| {
+                        let mut board = self.engine.board().clone();
+                        board.play_unchecked(mv);
+                        move_to_uci(&board, reply, self.chess960)
+                    });
+
+                    let _ = self.output.send(UciOutput::BestMove {
+                        best_move: uci_move,
+                        ponder,
+                    });
+                }
+                EngineCommand::Display => {
+                    let text = describe_position(
+                        self.engine.board(),
+                        self.engine.game_history_len(),
+                        self.chess960,
+                    );
+                    let _ = self.output.send(UciOutput::Text(text));
+                }
+                EngineCommand::Eval => {
+                    let report = self.engine.eval_report();
+                    let _ = self.output.send(UciOutput::Text(report.to_string()));
+                }
+                EngineCommand::Perft(depth) => {
+                    let board = self.engine.board();
+                    let divide: Vec<_> = perft_divide(board, depth)
+                        .into_iter()
+                        .map(|(mv, count)| (move_to_uci(board, mv, self.chess960), count))
+                        .collect();
+                    let nodes = divide.iter().map(|(_, count)| count).sum();
+
+                    let _ = self.output.send(UciOutput::Perft { divide, nodes });
+                }
+                EngineCommand::Bench(depth) => {
+                    let output = &self.output;
+                    let report = |line| {
+                        let _ = output.send(UciOutput::Text(line));
+                    };
+
+                    let text = match run_bench(depth, Arc::clone(&self.stop), report) {
+                        Ok(Some(result)) => result.to_string(),
+                        Ok(None) => "bench stopped".to_string(),
+                        Err(e) => format!("bench failed to load NNUE: {}", e),
+                    };
+                    let _ = self.output.send(UciOutput::Text(text));
                 }
                 EngineCommand::SetPosition { board, history } => {
                     self.engine.set_position(board, Some(history));
@@ -58,8 +142,18 @@ impl EngineWorker {
                     self.engine.new_game();
                 }
                 EngineCommand::Configure(config) => {
+                    self.chess960 = config.chess960.value;
                     self.engine.configure(&config, false);
                 }
+                EngineCommand::PressButton(name) => {
+                    self.engine.press_button(&name);
+                }
+                EngineCommand::SetNnue(nnue) => {
+                    self.engine.set_nnue(nnue);
+                }
+                EngineCommand::Debug(enabled) => {
+                    self.engine.set_debug(enabled);
+                }
                 EngineCommand::Quit => break,
             }
         }
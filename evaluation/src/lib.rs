@@ -1,6 +1,7 @@
 pub mod piece_values;
 pub mod scores;
 mod traits;
+pub mod wdl;
 
 pub use piece_values::PieceValues;
 pub use traits::{HCE, NNUE};
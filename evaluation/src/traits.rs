@@ -24,4 +24,6 @@ pub trait NNUE: Send {
     fn name(&self) -> String;
     /// Evaluate position from White's perspective. Positive = White advantage.
     fn evaluate(&mut self, board: &Board) -> i16;
+    /// Creates an independent copy with the same weights (e.g. for another search thread).
+    fn boxed_clone(&self) -> Box<dyn NNUE>;
 }
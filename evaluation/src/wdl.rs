@@ -0,0 +1,147 @@
+use cozy_chess::{Board, Piece};
+
+/// Win/draw/loss model: the probability of winning as a function of the score and
+/// the material left on the board.
+///
+/// The win probability is a logistic curve `1 / (1 + exp((a - score) / b))`, where
+/// `a` is the score at which winning is as likely as not, and `b` sets how quickly
+/// the outcome becomes certain. Both are cubic polynomials in the material count, as
+/// the same advantage converts more reliably with fewer pieces on the board.
+///
+/// Coefficients are fitted from `generate` self-play data with the `wdl` binary.
+/// <https://github.com/official-stockfish/WDL_model>
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WdlModel {
+    pub a: [f64; 4],
+    pub b: [f64; 4],
+}
+
+/// Model fitted from ~170k positions of depth 8 HCE self-play from the
+/// `Blitz_Testing_4moves` book, generated with `--deterministic` so that results
+/// follow the scores. About 43% of games are drawn at an even score from the start
+/// position, rising to about 70% in endgames.
+pub const WDL_MODEL: WdlModel = WdlModel {
+    a: [-243.980, 808.653, -900.704, 509.763],
+    b: [-155.652, 481.626, -422.390, 256.249],
+};
+
+/// Material range the model is fitted over. Counts outside it are clamped.
+pub const MIN_MATERIAL: i32 = 17;
+pub const MAX_MATERIAL: i32 = 78;
+/// Scale of the polynomial input: `x = material / 58`. This is not the starting
+/// material (78 on this 1/3/3/5/9 count), just the point where `x = 1`.
+pub const MATERIAL_NORMALIZATION: f64 = 58.0;
+
+/// Range the `wdl` fitter searches for `a` and `b` at each material count.
+/// A wider curve than `MAX_B` would mean the score barely predicts the result at all,
+/// which says more about the data than about the position.
+pub const MIN_A: f64 = 10.0;
+pub const MAX_A: f64 = 1000.0;
+pub const MIN_B: f64 = 5.0;
+pub const MAX_B: f64 = 400.0;
+
+/// Win/draw/loss probabilities in per mille, from the side to move's point of view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Wdl {
+    pub win: u16,
+    pub draw: u16,
+    pub loss: u16,
+}
+
+/// Material count of both sides, in pawns (1/3/3/5/9).
+pub fn material_count(board: &Board) -> i32 {
+    let count = |piece| board.pieces(piece).len() as i32;
+
+    count(Piece::Pawn)
+        + 3 * count(Piece::Knight)
+        + 3 * count(Piece::Bishop)
+        + 5 * count(Piece::Rook)
+        + 9 * count(Piece::Queen)
+}
+
+impl WdlModel {
+    /// Returns the `(a, b)` parameters of the logistic curve for a material count.
+    pub fn params(&self, material: i32) -> (f64, f64) {
+        let x = material.clamp(MIN_MATERIAL, MAX_MATERIAL) as f64 / MATERIAL_NORMALIZATION;
+
+        (polynomial(&self.a, x), polynomial(&self.b, x))
+    }
+
+    /// Probability of winning with the given score, in `0.0..=1.0`.
+    pub fn win_probability(&self, score: i16, material: i32) -> f64 {
+        let (a, b) = self.params(material);
+
+        1.0 / (1.0 + ((a - score as f64) / b).exp())
+    }
+
+    /// Win/draw/loss probabilities for a (non-mate) score.
+    pub fn wdl(&self, score: i16, material: i32) -> Wdl {
+        let win = (1000.0 * self.win_probability(score, material)).round() as u16;
+        let loss = (1000.0 * self.win_probability(-score, material)).round() as u16;
+
+        Wdl {
+            win,
+            draw: 1000 - win - loss,
+            loss,
+        }
+    }
+
+    /// Rescales a score so that 100 means a 50% chance of winning, whatever the material.
+    pub fn normalize_score(&self, score: i16, material: i32) -> i16 {
+        let (a, _) = self.params(material);
+
+        (score as f64 * 100.0 / a).round() as i16
+    }
+}
+
+fn polynomial(coefficients: &[f64; 4], x: f64) -> f64 {
+    coefficients.iter().fold(0.0, |acc, &c| acc * x + c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_material_count() {
+        assert_eq!(material_count(&Board::default()), 78);
+    }
+
+    #[test]
+    fn test_wdl_is_symmetric() {
+        let even = WDL_MODEL.wdl(0, 58);
+        assert_eq!(even.win, even.loss);
+        assert_eq!(even.win + even.draw + even.loss, 1000);
+
+        let winning = WDL_MODEL.wdl(300, 58);
+        let losing = WDL_MODEL.wdl(-300, 58);
+        assert_eq!(winning.win, losing.loss);
+        assert!(winning.win > winning.loss);
+    }
+
+    #[test]
+    fn test_normalized_score_is_half_win_probability() {
+        for material in [MIN_MATERIAL, 40, MAX_MATERIAL] {
+            let (a, _) = WDL_MODEL.params(material);
+            let score = a.round() as i16;
+
+            assert_eq!(WDL_MODEL.normalize_score(score, material), 100);
+            assert!((WDL_MODEL.win_probability(score, material) - 0.5).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn test_params_stay_in_fit_bounds() {
+        for material in MIN_MATERIAL..=MAX_MATERIAL {
+            let (a, b) = WDL_MODEL.params(material);
+            assert!(
+                (MIN_A..=MAX_A).contains(&a),
+                "a = {a} at material {material}"
+            );
+            assert!(
+                (MIN_B..=MAX_B).contains(&b),
+                "b = {b} at material {material}"
+            );
+        }
+    }
+}
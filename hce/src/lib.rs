@@ -9,10 +9,12 @@ mod eval_space;
 mod eval_threats;
 mod pawn_cache;
 mod pst;
+mod trace;
 
 pub use config::HCEConfig;
 use context::EvalContext;
 use pawn_cache::PawnCache;
+pub use trace::{EvalTrace, TermScore, TraceTerm};
 
 use crate::pawn_cache::CachedPawnEvaluation;
 use cozy_chess::Color;
@@ -0,0 +1,249 @@
+use std::fmt;
+
+use cozy_chess::Color;
+use utils::Position;
+
+use crate::context::EvalContext;
+use crate::{
+    eval_bishops, eval_king, eval_material, eval_pawns, eval_rooks, eval_space, eval_threats,
+    Evaluator,
+};
+
+/// Score of one evaluation term for one side.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TermScore {
+    /// Score in a pure middlegame (phase 1.0)
+    pub mg: i16,
+    /// Score in a pure endgame (phase 0.0)
+    pub eg: i16,
+    /// Score at the phase of the traced position, as used by `evaluate`
+    pub phased: i16,
+}
+
+/// One evaluation term, split by colour.
+#[derive(Debug, Clone)]
+pub struct TraceTerm {
+    pub name: &'static str,
+    pub white: TermScore,
+    pub black: TermScore,
+}
+
+impl TraceTerm {
+    /// Net contribution of the term from White's perspective.
+    pub fn total(&self) -> i16 {
+        self.white.phased - self.black.phased
+    }
+}
+
+/// Breakdown of the hand-crafted evaluation into its terms.
+#[derive(Debug, Clone)]
+pub struct EvalTrace {
+    pub phase: f32,
+    pub terms: Vec<TraceTerm>,
+}
+
+impl EvalTrace {
+    /// The evaluation from White's perspective. Equal to `HCE::evaluate`.
+    pub fn total(&self) -> i16 {
+        self.terms.iter().map(TraceTerm::total).sum()
+    }
+}
+
+type TermFn = fn(&Evaluator, &EvalContext, Color) -> i16;
+
+/// The terms summed by `HCE::evaluate`, in the same order.
+const TERMS: &[(&str, TermFn)] = &[
+    ("Material", |e, ctx, color| {
+        eval_material::evaluate(ctx, color, &e.piece_values)
+    }),
+    ("Pawns", |e, ctx, color| {
+        eval_pawns::evaluate(ctx, color, &e.config)
+    }),
+    ("Rooks", |e, ctx, color| {
+        eval_rooks::evaluate(ctx, color, &e.config)
+    }),
+    ("Bishops", |e, ctx, color| {
+        eval_bishops::evaluate(ctx, color, &e.config)
+    }),
+    ("King", |e, ctx, color| {
+        eval_king::evaluate(ctx, color, &e.config)
+    }),
+    ("Space", |e, ctx, color| {
+        eval_space::evaluate(ctx, color, &e.config)
+    }),
+    ("Support", |e, ctx, color| {
+        eval_space::evaluate_support(ctx, color, &e.config)
+    }),
+    ("Threats", |e, ctx, color| {
+        eval_threats::evaluate(ctx, color, &e.config)
+    }),
+    ("Tempo", |e, ctx, color| {
+        if ctx.position.board.side_to_move() == color {
+            e.config.tempo_bonus
+        } else {
+            0
+        }
+    }),
+];
+
+impl Evaluator {
+    /// Evaluates like `HCE::evaluate`, but returns every term per colour at
+    /// middlegame, endgame and the given phase. Slow: for debugging only.
+    pub fn trace(&self, position: &Position, phase: f32) -> EvalTrace {
+        let mg = EvalContext::new(position, 1.0);
+        let eg = EvalContext::new(position, 0.0);
+        let phased = EvalContext::new(position, phase);
+
+        let score = |term: TermFn, color| TermScore {
+            mg: term(self, &mg, color),
+            eg: term(self, &eg, color),
+            phased: term(self, &phased, color),
+        };
+
+        let terms = TERMS
+            .iter()
+            .map(|&(name, term)| TraceTerm {
+                name,
+                white: score(term, Color::White),
+                black: score(term, Color::Black),
+            })
+            .collect();
+
+        EvalTrace { phase, terms }
+    }
+}
+
+impl fmt::Display for EvalTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "     Term    |     White     |     Black     |          Total"
+        )?;
+        writeln!(
+            f,
+            "             |    MG     EG  |    MG     EG  |    MG     EG  Phased"
+        )?;
+        writeln!(
+            f,
+            " ------------+---------------+---------------+--------------------"
+        )?;
+
+        for term in &self.terms {
+            writeln!(
+                f,
+                " {:>11} | {:>5}  {:>5}  | {:>5}  {:>5}  | {:>5}  {:>5}  {:>6}",
+                term.name,
+                term.white.mg,
+                term.white.eg,
+                term.black.mg,
+                term.black.eg,
+                term.white.mg - term.black.mg,
+                term.white.eg - term.black.eg,
+                term.total()
+            )?;
+        }
+
+        writeln!(
+            f,
+            " ------------+---------------+---------------+--------------------"
+        )?;
+        write!(
+            f,
+            " {:>11} | phase {:.2}                              {:>6}",
+            "Total",
+            self.phase,
+            self.total()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cozy_chess::Board;
+    use evaluation::{PieceValues, HCE};
+    use utils::game_phase;
+
+    use super::*;
+    use crate::HCEConfig;
+
+    fn evaluator() -> Evaluator {
+        let piece_values = PieceValues {
+            pawn_value_mg: 98.0,
+            pawn_value_eg: 113.0,
+            knight_value_mg: 325.0,
+            knight_value_eg: 340.0,
+            bishop_value_mg: 335.0,
+            bishop_value_eg: 350.0,
+            rook_value_mg: 510.0,
+            rook_value_eg: 560.0,
+            queen_value_mg: 975.0,
+            queen_value_eg: 1020.0,
+        };
+        let config = HCEConfig {
+            tempo_bonus: 10,
+            doubled_pawn_penalty: 30,
+            tripled_pawn_penalty: 60,
+            isolated_pawn_penalty: 39,
+            backward_pawn_penalty: 20,
+            backward_pawn_half_open_penalty: 10,
+            passed_pawn_linear: 6,
+            passed_pawn_quadratic: 5,
+            center_pawn_bonus: 20,
+            bishop_pair_bonus: 50,
+            rook_open_file_bonus: 15,
+            rook_semi_open_file_bonus: 10,
+            rook_seventh_rank_bonus: 20,
+            space_multiplier: 4,
+            supported_minor_bonus: 5,
+            supported_major_bonus: 10,
+            king_shield_r1_bonus: 12,
+            king_shield_r2_bonus: 6,
+            king_open_file_penalty: 24,
+            king_semi_open_file_penalty: 12,
+            king_thin_cover_penalty: 6,
+            king_pressure_knight: 12,
+            king_pressure_bishop: 14,
+            king_pressure_rook: 18,
+            king_pressure_queen: 22,
+            king_pressure_pawn: 8,
+            king_central_penalty: 20,
+            king_activity_bonus: 14,
+            threats_multiplier: 50,
+        };
+        Evaluator::new(piece_values, config)
+    }
+
+    #[test]
+    fn test_trace_total_matches_evaluate() {
+        let fens = [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "r2q1rk1/pP1p2pp/Q4n2/bbp1p3/Np6/1B3NBn/pPPP1PPP/R3K2R b KQ - 0 1",
+            "2r3k1/1q3pp1/p3p2p/1p1nP3/3P4/P2Q1N2/1P3PPP/2R3K1 b - - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 b - - 0 1",
+            "8/8/4k3/8/8/3NK3/8/8 w - - 0 1",
+        ];
+
+        let mut evaluator = evaluator();
+        for fen in fens {
+            let board: Board = fen.parse().unwrap();
+            let position = Position::new(&board);
+            let phase = game_phase(&board);
+
+            let trace = evaluator.trace(&position, phase);
+            // Twice, so the second call goes through the pawn cache
+            assert_eq!(
+                trace.total(),
+                evaluator.evaluate(&position, phase),
+                "{}",
+                fen
+            );
+            assert_eq!(
+                trace.total(),
+                evaluator.evaluate(&position, phase),
+                "{}",
+                fen
+            );
+        }
+    }
+}
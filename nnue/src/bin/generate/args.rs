@@ -10,10 +10,23 @@ pub struct Args {
     pub depth: u8,
 
     /// Path to opening book file (EPD format).
-    #[arg(long)]
-    pub book: String,
+    #[arg(
+        long,
+        required_unless_present = "chess960",
+        conflicts_with = "chess960"
+    )]
+    pub book: Option<String>,
+
+    /// Start games from random Chess960 starting positions instead of an opening book.
+    #[arg(long, default_value_t = false)]
+    pub chess960: bool,
 
     /// Use existing NNUE for evaluation instead of HCE.
     #[arg(long, default_value_t = false)]
     pub nnue: bool,
+
+    /// Always play the best move instead of random opening moves, so game results
+    /// follow the recorded scores. Use this for WDL model data.
+    #[arg(long, default_value_t = false)]
+    pub deterministic: bool,
 }
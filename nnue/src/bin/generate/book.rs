@@ -1,3 +1,4 @@
+use cozy_chess::Board;
 use rand::Rng;
 use std::error::Error;
 use std::fs::File;
@@ -10,10 +11,13 @@ const EPD_MIN_FIELDS: usize = 4;
 const DEFAULT_HALFMOVE: u8 = 0;
 const DEFAULT_FULLMOVE: u16 = 1;
 
+const CHESS960_POSITIONS: u32 = 960;
+
 /// Opening book loaded from EPD file.
 /// Provides balanced, known opening positions to start self-play from.
 pub struct Book {
     positions: Vec<String>,
+    chess960: bool,
 }
 
 impl Book {
@@ -34,7 +38,29 @@ impl Book {
 
         log::info!("Loaded {} positions from opening book", positions.len());
 
-        Ok(Self { positions })
+        Ok(Self {
+            positions,
+            chess960: false,
+        })
+    }
+
+    /// All 960 Chess960 starting positions, in Shredder-FEN.
+    pub fn chess960() -> Self {
+        let positions = (0..CHESS960_POSITIONS)
+            .map(|scharnagl_number| format!("{:#}", Board::chess960_startpos(scharnagl_number)))
+            .collect();
+
+        log::info!("Using {} Chess960 starting positions", CHESS960_POSITIONS);
+
+        Self {
+            positions,
+            chess960: true,
+        }
+    }
+
+    /// Whether positions may have Chess960 castling rights (non-standard rook files).
+    pub fn is_chess960(&self) -> bool {
+        self.chess960
     }
 
     pub fn random_position(&self) -> &str {
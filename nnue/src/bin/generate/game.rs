@@ -1,11 +1,10 @@
-use cozy_chess::{Board, Move};
+use cozy_chess::{Board, Color, Move};
 use rand::Rng;
-use search::Engine;
-use std::collections::HashMap;
+use search::{Engine, SearchLimits};
 use std::str::FromStr;
-use uci::commands::GoParams;
 use utils::{
-    collect_legal_moves, flip_eval_perspective, has_insufficient_material, has_legal_moves,
+    collect_legal_moves, flip_eval_perspective, has_check, has_insufficient_material,
+    has_legal_moves, is_fifty_move_draw, GameHistory,
 };
 
 // Temperature-based move selection for diversity in training data.
@@ -17,26 +16,57 @@ const MIN_TEMPERATURE: f32 = 0.05;
 // TODO: Consider re-using and sharing with search crate
 const MATE_THRESHOLD: i16 = 5000;
 
-/// A self-play game that generates training samples: (FEN, score, game_id) tuples.
+/// Outcome of a self-play game.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameResult {
+    Win(Color),
+    Draw,
+}
+
+impl GameResult {
+    /// Result from white's perspective: 1 for a win, 0.5 for a draw and 0 for a loss.
+    pub fn white_score(&self) -> f32 {
+        match self {
+            GameResult::Win(Color::White) => 1.0,
+            GameResult::Win(Color::Black) => 0.0,
+            GameResult::Draw => 0.5,
+        }
+    }
+}
+
+/// A training sample: FEN, white-relative score, game id and white-relative game result.
+pub type GameSample = (String, i16, usize, f32);
+
+/// A self-play game that generates training samples: (FEN, score, game_id, result) tuples.
 /// Plays from an opening position until terminal, recording evaluations.
 pub struct SelfPlayGame {
     board: Board,
     game_id: usize,
+    /// Record positions as Shredder-FEN, since standard FEN can't express Chess960 castling rights
+    chess960: bool,
+    /// Skip the random opening moves
+    deterministic: bool,
     ply_count: usize,
-    position_counts: HashMap<u64, usize>,
+    /// Positions before the current one, for the repetition rule
+    history: GameHistory,
     current_game_samples: Vec<(String, i16)>,
+    /// Set when the game ends, so each sample can be labelled with the outcome
+    result: Option<GameResult>,
 }
 
 impl SelfPlayGame {
-    pub fn new(game_id: usize, opening_fen: &str) -> Self {
+    pub fn new(game_id: usize, opening_fen: &str, chess960: bool, deterministic: bool) -> Self {
         let board = Board::from_str(opening_fen).unwrap();
 
         Self {
             board,
             game_id,
+            chess960,
+            deterministic,
             ply_count: 0,
-            position_counts: HashMap::new(),
+            history: GameHistory::new(),
             current_game_samples: Vec::new(),
+            result: None,
         }
     }
 
@@ -53,6 +83,10 @@ impl SelfPlayGame {
             // Skip near-mate positions
             // Testing showed this improves strength (by freeing capacity for nuanced positions, I guess)
             if eval.abs() >= MATE_THRESHOLD {
+                // Adjudicate: the side with the mate score wins
+                let stm = self.board.side_to_move();
+                let winner = if eval > 0 { stm } else { !stm };
+                self.result = Some(GameResult::Win(winner));
                 break;
             }
 
@@ -62,33 +96,34 @@ impl SelfPlayGame {
     }
 
     fn compute_move(&self, engine: &mut Engine, depth: u8) -> (Move, i16) {
-        let history = self.history();
-
-        engine.set_position(self.board.clone(), Some(history));
+        engine.set_position(self.board.clone(), Some(self.history.clone()));
 
-        let params = GoParams {
+        let limits = SearchLimits {
             depth: Some(depth),
             ..Default::default()
         };
 
-        engine.search(&params, None).unwrap()
+        engine.search(&limits, None).unwrap()
     }
 
     fn is_terminal(&mut self) -> bool {
         if !has_legal_moves(&self.board) {
+            self.result = Some(if has_check(&self.board) {
+                GameResult::Win(!self.board.side_to_move())
+            } else {
+                GameResult::Draw
+            });
             return true;
         }
 
         if has_insufficient_material(&self.board) {
+            self.result = Some(GameResult::Draw);
             return true;
         }
 
-        // Check position repetition (abort on first repetition)
-        // For training data, we don't need official three-fold rule -
-        // any repetition means the game is cycling and won't produce useful data
-        let board_hash = self.board.hash();
-        *self.position_counts.entry(board_hash).or_insert(0) += 1;
-        if self.position_counts[&board_hash] >= 2 {
+        // Same draw rules as the search: fifty-move rule and threefold repetition
+        if is_fifty_move_draw(&self.board) || self.history.count(self.board.hash()) >= 2 {
+            self.result = Some(GameResult::Draw);
             return true;
         }
 
@@ -99,8 +134,13 @@ impl SelfPlayGame {
         // Engine score is from STM perspective; flip to white's perspective for training
         let white_score = flip_eval_perspective(self.board.side_to_move(), engine_score);
 
-        self.current_game_samples
-            .push((format!("{}", self.board), white_score));
+        let fen = if self.chess960 {
+            format!("{:#}", self.board)
+        } else {
+            format!("{}", self.board)
+        };
+
+        self.current_game_samples.push((fen, white_score));
     }
 
     /// Selects a move using temperature-based randomization.
@@ -115,7 +155,7 @@ impl SelfPlayGame {
         let move_number = self.ply_count / 2;
         let temp = INITIAL_TEMPERATURE * (-(move_number as f32) / TEMPERATURE_DECAY_RATE).exp();
 
-        if temp < MIN_TEMPERATURE {
+        if self.deterministic || temp < MIN_TEMPERATURE {
             return best_move;
         }
 
@@ -137,24 +177,17 @@ impl SelfPlayGame {
 
     fn make_move(&mut self, best_move: Move) {
         let chosen_move = self.select_move(best_move);
-        self.board.play_unchecked(chosen_move);
+        self.history.play(&mut self.board, chosen_move);
         self.ply_count += 1;
     }
 
-    fn history(&self) -> ahash::AHashSet<u64> {
-        let current_hash = self.board.hash();
-        self.position_counts
-            .keys()
-            .copied()
-            .filter(|&hash| hash != current_hash)
-            .collect()
-    }
+    pub fn drain_samples(&mut self) -> (Vec<GameSample>, Vec<i16>) {
+        let result = self.result.map_or(0.5, |result| result.white_score());
 
-    pub fn drain_samples(&mut self) -> (Vec<(String, i16, usize)>, Vec<i16>) {
         let (samples, scores): (Vec<_>, Vec<_>) = self
             .current_game_samples
             .drain(..)
-            .map(|(fen, score)| ((fen, score, self.game_id), score))
+            .map(|(fen, score)| ((fen, score, self.game_id, result), score))
             .unzip();
         (samples, scores)
     }
@@ -1,4 +1,5 @@
 use crate::book::Book;
+use crate::game::GameSample;
 use crate::histogram::ScoreHistogram;
 use crate::worker::SelfPlayWorker;
 use candle_core::Device;
@@ -26,9 +27,13 @@ impl Generator {
     pub fn new(
         threads: usize,
         use_nnue: bool,
-        opening_book_path: String,
+        opening_book_path: Option<String>,
     ) -> Result<Self, Box<dyn Error>> {
-        let opening_book = Arc::new(Book::load(&opening_book_path)?);
+        // Without a book, games start from the Chess960 starting positions
+        let opening_book = Arc::new(match opening_book_path {
+            Some(path) => Book::load(&path)?,
+            None => Book::chess960(),
+        });
 
         let nnue_path = if use_nnue {
             let path = PathBuf::from(DEFAULT_NNUE_PATH);
@@ -51,7 +56,12 @@ impl Generator {
         })
     }
 
-    pub fn run(&self, depth: u8, stop_flag: Arc<AtomicBool>) -> Vec<(String, i16, usize)> {
+    pub fn run(
+        &self,
+        depth: u8,
+        deterministic: bool,
+        stop_flag: Arc<AtomicBool>,
+    ) -> Vec<GameSample> {
         log::info!(
             "Generating samples using {} threads - Press Ctrl+C to stop",
             self.threads,
@@ -81,6 +91,7 @@ impl Generator {
                         sample_counter,
                         game_id_counter,
                         depth,
+                        deterministic,
                         nnue,
                         opening_book,
                         histogram_handle,
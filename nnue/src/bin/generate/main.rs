@@ -35,7 +35,7 @@ fn main() -> Result<(), Box<dyn Error>> {
     })?;
 
     let generator = Generator::new(num_cpus::get(), args.nnue, args.book)?;
-    let evaluations = generator.run(args.depth, stop_flag);
+    let evaluations = generator.run(args.depth, args.deterministic, stop_flag);
 
     let samples = Samples::from_evaluations(&evaluations);
 
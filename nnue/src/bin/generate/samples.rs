@@ -1,3 +1,4 @@
+use crate::game::GameSample;
 use nnue::network::CP_BOUND;
 use std::io::{self, Write};
 
@@ -6,33 +7,38 @@ pub struct Samples {
     pub fens: Vec<Box<str>>,
     pub scores: Vec<i16>,
     pub game_ids: Vec<usize>,
+    /// Game result from white's perspective (1, 0.5 or 0)
+    pub results: Vec<f32>,
 }
 
 impl Samples {
-    pub fn from_evaluations(evals: &[(String, i16, usize)]) -> Self {
+    pub fn from_evaluations(evals: &[GameSample]) -> Self {
         let mut fens = Vec::with_capacity(evals.len());
         let mut scores = Vec::with_capacity(evals.len());
         let mut game_ids = Vec::with_capacity(evals.len());
-        for (fen, score, game_id) in evals.iter() {
+        let mut results = Vec::with_capacity(evals.len());
+        for (fen, score, game_id, result) in evals.iter() {
             fens.push(fen.clone().into_boxed_str());
             scores.push((*score).clamp(-CP_BOUND, CP_BOUND));
             game_ids.push(*game_id);
+            results.push(*result);
         }
         Self {
             fens,
             scores,
             game_ids,
+            results,
         }
     }
 
     pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
-        writeln!(writer, "fen,score,game_id")?; // Header
+        writeln!(writer, "fen,score,game_id,result")?; // Header
 
         for i in 0..self.fens.len() {
             writeln!(
                 writer,
-                "{},{},{}",
-                self.fens[i], self.scores[i], self.game_ids[i]
+                "{},{},{},{}",
+                self.fens[i], self.scores[i], self.game_ids[i], self.results[i]
             )?;
         }
 
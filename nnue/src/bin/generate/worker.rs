@@ -1,5 +1,5 @@
 use crate::book::Book;
-use crate::game::SelfPlayGame;
+use crate::game::{GameSample, SelfPlayGame};
 use crate::histogram::HistogramHandle;
 use evaluation::NNUE;
 use search::{Engine, EngineConfig};
@@ -17,16 +17,19 @@ pub struct SelfPlayWorker {
     game_id_counter: Arc<AtomicUsize>,
     engine: Engine,
     depth: u8,
+    deterministic: bool,
     opening_book: Arc<Book>,
     histogram: HistogramHandle,
 }
 
 impl SelfPlayWorker {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         tid: usize,
         sample_counter: Arc<AtomicUsize>,
         game_id_counter: Arc<AtomicUsize>,
         depth: u8,
+        deterministic: bool,
         nnue: Option<Box<dyn NNUE>>,
         opening_book: Arc<Book>,
         histogram: HistogramHandle,
@@ -49,20 +52,26 @@ impl SelfPlayWorker {
             sample_counter,
             game_id_counter,
             depth,
+            deterministic,
             engine: Engine::new(&config, hce, nnue, stop),
             opening_book,
             histogram,
         }
     }
 
-    pub fn play_games(&mut self, stop_flag: Arc<AtomicBool>) -> Vec<(String, i16, usize)> {
+    pub fn play_games(&mut self, stop_flag: Arc<AtomicBool>) -> Vec<GameSample> {
         let mut evaluations = Vec::new();
 
         while !stop_flag.load(Ordering::Relaxed) {
             let game_id = self.game_id_counter.fetch_add(1, Ordering::Relaxed);
             let opening_fen = self.opening_book.random_position();
 
-            let mut game = SelfPlayGame::new(game_id, opening_fen);
+            let mut game = SelfPlayGame::new(
+                game_id,
+                opening_fen,
+                self.opening_book.is_chess960(),
+                self.deterministic,
+            );
             game.play(&mut self.engine, self.depth);
 
             let (samples, scores) = game.drain_samples();
@@ -74,7 +83,7 @@ impl SelfPlayWorker {
         evaluations
     }
 
-    fn record_statistics(&self, samples: &[(String, i16, usize)], scores: Vec<i16>) {
+    fn record_statistics(&self, samples: &[GameSample], scores: Vec<i16>) {
         let num_samples = samples.len();
 
         self.histogram.record_scores(&scores);
@@ -0,0 +1,15 @@
+use clap::Parser;
+
+#[derive(Parser, Debug)]
+#[command(name = "WDL Model Fitter")]
+#[command(author = "Jørgen Hanssen <jorgen@hanssen.io>")]
+#[command(version = "0.1.0")]
+pub struct Args {
+    /// Directory with `generate` output (CSV files with a result column).
+    #[arg(long, default_value = "nnue/data")]
+    pub data: String,
+
+    /// Minimum number of positions for a material count to be used in the fit.
+    #[arg(long, default_value_t = 1000)]
+    pub min_samples: u64,
+}
@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use cozy_chess::Board;
+use evaluation::wdl::material_count;
+
+/// Game outcome counts for positions with the same material and score.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Outcomes {
+    pub wins: u64,
+    pub draws: u64,
+    pub losses: u64,
+}
+
+impl Outcomes {
+    pub fn total(&self) -> u64 {
+        self.wins + self.draws + self.losses
+    }
+}
+
+/// Outcome counts keyed by material count, then by score (both from white's perspective).
+pub type OutcomeTable = HashMap<i32, HashMap<i16, Outcomes>>;
+
+/// Reads all CSV files in `data_dir`. Rows without a game result are skipped.
+pub fn load_outcomes(data_dir: &Path) -> Result<(OutcomeTable, u64), Box<dyn Error>> {
+    let mut table = OutcomeTable::new();
+    let mut positions = 0;
+
+    for path in csv_files(data_dir)? {
+        let reader = BufReader::new(File::open(&path)?);
+
+        // Skip header
+        for line in reader.lines().skip(1) {
+            let line = line?;
+            let Some((board, score, result)) = parse_line(&line) else {
+                continue;
+            };
+
+            let outcomes = table
+                .entry(material_count(&board))
+                .or_default()
+                .entry(score)
+                .or_default();
+
+            match result {
+                r if r > 0.5 => outcomes.wins += 1,
+                r if r < 0.5 => outcomes.losses += 1,
+                _ => outcomes.draws += 1,
+            }
+            positions += 1;
+        }
+    }
+
+    Ok((table, positions))
+}
+
+fn parse_line(line: &str) -> Option<(Board, i16, f32)> {
+    let mut parts = line.trim().split(',');
+    let board = Board::from_str(parts.next()?).ok()?;
+    let score: i16 = parts.next()?.parse().ok()?;
+    let _game_id = parts.next()?;
+    let result: f32 = parts.next()?.parse().ok()?;
+
+    Some((board, score, result))
+}
+
+fn csv_files(data_dir: &Path) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut files: Vec<_> = fs::read_dir(data_dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "csv"))
+        .collect();
+    files.sort();
+    Ok(files)
+}
@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+use evaluation::wdl::{MAX_A, MAX_B, MIN_A, MIN_B};
+
+use crate::data::Outcomes;
+
+const GRID_STEPS: usize = 40;
+const REFINEMENTS: usize = 6;
+
+/// Fits the `(a, b)` parameters of the logistic win probability for one material count
+/// by maximizing the likelihood of the observed outcomes.
+///
+/// A coarse grid search that repeatedly zooms in around the best point. With only two
+/// parameters this is fast and, unlike gradient descent, needs no tuning.
+pub fn fit_logistic(outcomes: &HashMap<i16, Outcomes>) -> (f64, f64) {
+    let (mut a_range, mut b_range) = ((MIN_A, MAX_A), (MIN_B, MAX_B));
+    let mut best = (0.0, 0.0);
+
+    for _ in 0..REFINEMENTS {
+        let mut best_loss = f64::INFINITY;
+
+        for a in grid(a_range) {
+            for b in grid(b_range) {
+                let loss = negative_log_likelihood(outcomes, a, b);
+                if loss < best_loss {
+                    best_loss = loss;
+                    best = (a, b);
+                }
+            }
+        }
+
+        // Zoom in to two grid cells around the best point, never leaving the search range
+        let a_step = (a_range.1 - a_range.0) / GRID_STEPS as f64;
+        let b_step = (b_range.1 - b_range.0) / GRID_STEPS as f64;
+        a_range = (
+            (best.0 - 2.0 * a_step).max(MIN_A),
+            (best.0 + 2.0 * a_step).min(MAX_A),
+        );
+        b_range = (
+            (best.1 - 2.0 * b_step).max(MIN_B),
+            (best.1 + 2.0 * b_step).min(MAX_B),
+        );
+    }
+
+    best
+}
+
+fn grid((min, max): (f64, f64)) -> impl Iterator<Item = f64> {
+    (0..=GRID_STEPS).map(move |i| min + (max - min) * i as f64 / GRID_STEPS as f64)
+}
+
+fn negative_log_likelihood(outcomes: &HashMap<i16, Outcomes>, a: f64, b: f64) -> f64 {
+    const EPSILON: f64 = 1e-9;
+
+    outcomes
+        .iter()
+        .map(|(&score, counts)| {
+            let win = logistic(score as f64, a, b);
+            let loss = logistic(-score as f64, a, b);
+            let draw = (1.0 - win - loss).max(EPSILON);
+
+            -(counts.wins as f64 * win.max(EPSILON).ln()
+                + counts.draws as f64 * draw.ln()
+                + counts.losses as f64 * loss.max(EPSILON).ln())
+        })
+        .sum()
+}
+
+fn logistic(score: f64, a: f64, b: f64) -> f64 {
+    1.0 / (1.0 + ((a - score) / b).exp())
+}
+
+/// Weighted least-squares fit of a cubic polynomial, highest degree first.
+pub fn fit_cubic(points: &[(f64, f64, f64)]) -> [f64; 4] {
+    const DEGREE: usize = 3;
+
+    // Normal equations (X^T W X) c = X^T W y, with columns x^3, x^2, x, 1
+    let mut matrix = [[0.0; DEGREE + 2]; DEGREE + 1];
+    for &(x, y, weight) in points {
+        let powers: [f64; DEGREE + 1] = std::array::from_fn(|i| x.powi((DEGREE - i) as i32));
+
+        for row in 0..=DEGREE {
+            for col in 0..=DEGREE {
+                matrix[row][col] += weight * powers[row] * powers[col];
+            }
+            matrix[row][DEGREE + 1] += weight * powers[row] * y;
+        }
+    }
+
+    solve(matrix)
+}
+
+/// Gaussian elimination with partial pivoting on an augmented 4x5 matrix.
+fn solve(mut matrix: [[f64; 5]; 4]) -> [f64; 4] {
+    let n = matrix.len();
+
+    for col in 0..n {
+        let pivot = (col..n)
+            .max_by(|&i, &j| matrix[i][col].abs().total_cmp(&matrix[j][col].abs()))
+            .unwrap();
+        matrix.swap(col, pivot);
+
+        let pivot_row = matrix[col];
+        for row in matrix.iter_mut().skip(col + 1) {
+            let factor = row[col] / pivot_row[col];
+            for (value, pivot_value) in row[col..].iter_mut().zip(&pivot_row[col..]) {
+                *value -= factor * pivot_value;
+            }
+        }
+    }
+
+    let mut solution = [0.0; 4];
+    for row in (0..n).rev() {
+        let sum: f64 = (row + 1..n).map(|k| matrix[row][k] * solution[k]).sum();
+        solution[row] = (matrix[row][n] - sum) / matrix[row][row];
+    }
+
+    solution
+}
@@ -0,0 +1,66 @@
+mod args;
+mod data;
+mod fit;
+
+use args::Args;
+use clap::Parser;
+use evaluation::wdl::{WdlModel, MATERIAL_NORMALIZATION, MAX_MATERIAL, MIN_MATERIAL};
+use log::LevelFilter;
+use simplelog::{Config, SimpleLogger};
+use std::{error::Error, path::Path};
+
+/// Fits the win/draw/loss model in `evaluation::wdl` from `generate` output.
+///
+/// Each material count gets its own logistic curve, then the curve parameters are
+/// smoothed with cubic polynomials in the material count.
+fn main() -> Result<(), Box<dyn Error>> {
+    SimpleLogger::init(LevelFilter::Info, Config::default())?;
+
+    let args = Args::parse();
+
+    let (table, positions) = data::load_outcomes(Path::new(&args.data))?;
+    log::info!("Loaded {} positions with game results", positions);
+
+    let mut a_points = Vec::new();
+    let mut b_points = Vec::new();
+
+    for material in MIN_MATERIAL..=MAX_MATERIAL {
+        let Some(outcomes) = table.get(&material) else {
+            continue;
+        };
+
+        let count: u64 = outcomes.values().map(|o| o.total()).sum();
+        if count < args.min_samples {
+            continue;
+        }
+
+        let (a, b) = fit::fit_logistic(outcomes);
+        log::info!(
+            "material {:2}: a = {:7.2}, b = {:7.2} ({} positions)",
+            material,
+            a,
+            b,
+            count
+        );
+
+        let x = material as f64 / MATERIAL_NORMALIZATION;
+        a_points.push((x, a, count as f64));
+        b_points.push((x, b, count as f64));
+    }
+
+    if a_points.len() < 4 {
+        return Err("not enough material counts with data to fit the model".into());
+    }
+
+    let model = WdlModel {
+        a: fit::fit_cubic(&a_points),
+        b: fit::fit_cubic(&b_points),
+    };
+
+    println!("pub const WDL_MODEL: WdlModel = WdlModel {{");
+    println!("    a: {:?},", model.a);
+    println!("    b: {:?},", model.b);
+    println!("}};");
+
+    Ok(())
+}
@@ -7,13 +7,14 @@ use crate::{
     encoding::encode_board_bitset,
     network::{NNUENetwork, Network},
 };
-use candle_core::{DType, Device};
+use candle_core::{bail, safetensors::SliceSafetensors, DType, Device};
 
 /// NNUE evaluator for inference.
 ///
 /// The `network` field exists because candle's VarMap requires creating the network structure
 /// first (which registers tensors), then loading weights. After loading, `enable_nnue()` creates
 /// the quantized network from the loaded weights.
+#[derive(Clone)]
 pub struct Evaluator {
     /// Quantized network for fast inference
     nnue: Option<NNUENetwork>,
@@ -35,6 +36,47 @@ impl Evaluator {
     pub fn enable_nnue(&mut self) {
         self.nnue = Some(NNUENetwork::from_network(&self.network).unwrap());
     }
+
+    /// Loads weights from safetensors data and enables the quantized network.
+    ///
+    /// Every tensor of [`Network`] must be present with the expected shape and dtype,
+    /// so a net from an incompatible architecture is rejected instead of misbehaving.
+    pub fn from_safetensors(bytes: &[u8]) -> candle_core::Result<Self> {
+        let varmap = VarMap::new();
+        let mut evaluator = Self::new(&varmap, &Device::Cpu);
+
+        let st = SliceSafetensors::new(bytes)?;
+        {
+            let mut tensor_data = varmap.data().lock().unwrap();
+
+            for (name, var) in tensor_data.iter_mut() {
+                let tensor = st.load(name, var.device())?;
+
+                if tensor.shape() != var.shape() {
+                    bail!(
+                        "tensor '{}' has shape {:?}, expected {:?}",
+                        name,
+                        tensor.shape(),
+                        var.shape()
+                    );
+                }
+                if tensor.dtype() != var.dtype() {
+                    bail!(
+                        "tensor '{}' has dtype {:?}, expected {:?}",
+                        name,
+                        tensor.dtype(),
+                        var.dtype()
+                    );
+                }
+
+                var.set(&tensor)?;
+            }
+        }
+
+        evaluator.enable_nnue();
+
+        Ok(evaluator)
+    }
 }
 
 impl NNUE for Evaluator {
@@ -67,4 +109,46 @@ impl NNUE for Evaluator {
             .forward(&bitset)
             .clamp(i16::MIN as f32, i16::MAX as f32) as i16
     }
+
+    fn boxed_clone(&self) -> Box<dyn NNUE> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use candle_core::Tensor;
+
+    use super::*;
+
+    fn random_net_bytes() -> Vec<u8> {
+        let varmap = VarMap::new();
+        let _ = Evaluator::new(&varmap, &Device::Cpu);
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        varmap.save(file.path()).unwrap();
+        std::fs::read(file.path()).unwrap()
+    }
+
+    #[test]
+    fn test_from_safetensors() {
+        let mut evaluator = Evaluator::from_safetensors(&random_net_bytes()).unwrap();
+        evaluator.evaluate(&Board::default());
+    }
+
+    #[test]
+    fn test_from_safetensors_rejects_wrong_shape() {
+        let tensors = HashMap::from([(
+            "embedding.weight".to_string(),
+            Tensor::zeros((2, 2), DType::F32, &Device::Cpu).unwrap(),
+        )]);
+        let file = tempfile::NamedTempFile::new().unwrap();
+        candle_core::safetensors::save(&tensors, file.path()).unwrap();
+
+        let bytes = std::fs::read(file.path()).unwrap();
+        assert!(Evaluator::from_safetensors(&bytes).is_err());
+        assert!(Evaluator::from_safetensors(b"not a safetensors file").is_err());
+    }
 }
@@ -18,6 +18,7 @@ use super::{EMBEDDING_SIZE, QUANTIZATION_PERCENTILE};
 ///
 /// Weights are quantized to i8 and accumulated in i16 for speed (SIMD-friendly).
 /// Dequantization back to f32 happens only when outputting to the next layer.
+#[derive(Clone)]
 pub struct Accumulator {
     // [feature_idx][embedding_idx]
     weights: Box<[i8]>,
@@ -11,6 +11,7 @@ use super::{CP_BOUND, EMBEDDING_SIZE, FV_SCALE, HIDDEN_SIZE};
 
 /// Main NNUE inference engine with quantized weights for fast evaluation.
 /// Uses an incremental accumulator for the embedding layer.
+#[derive(Clone)]
 pub struct NNUENetwork {
     accumulator: Accumulator,
     hidden1: LinearLayer,
@@ -8,6 +8,7 @@ use super::simd::dot_product;
 /// Uses SIMD-accelerated dot products and pre-flattened weight storage.
 /// Unlike the embedding layer, this is not quantized since the hidden layers
 /// are small enough that f32 performance is acceptable.
+#[derive(Clone)]
 pub struct LinearLayer {
     weights: Box<[f32]>,
     biases: Box<[f32]>,
@@ -6,6 +6,7 @@ use crate::encoding::NUM_FEATURES;
 use super::{EMBEDDING_SIZE, HIDDEN_SIZE};
 
 /// Full-precision network for training and weight loading (via Candle).
+#[derive(Clone)]
 pub struct Network {
     pub(crate) embedding: Linear,
     pub(crate) hidden1: Linear,
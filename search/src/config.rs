@@ -1,33 +1,40 @@
+use std::convert::Infallible;
+use std::fmt;
 use std::str::FromStr;
 
+use crate::engine::MAX_SKILL_LEVEL;
+use crate::extensions::ExtensionConfig;
+use crate::options::{EngineOption, OptionType};
 use evaluation::PieceValues;
 use hce::HCEConfig;
-use uci::{UciOption, UciOptionType, UciOutput};
 
-/// Helper to conditionally create UCI option metadata.
-/// If `include` is false (e.g., tuning feature disabled), option won't appear in UCI.
-fn uci(include: bool, name: &'static str, option_type: UciOptionType) -> Option<UciOption> {
+/// Helper to conditionally create option metadata.
+/// If `include` is false (e.g., tuning feature disabled), the option isn't exposed.
+fn option(include: bool, name: &'static str, option_type: OptionType) -> Option<EngineOption> {
     if include {
-        Some(UciOption { name, option_type })
+        Some(EngineOption { name, option_type })
     } else {
         None
     }
 }
 
-/// Generates EngineConfig struct and UCI plumbing from a list of parameters.
+/// Generates EngineConfig struct and option plumbing from a list of parameters.
 ///
-/// Each entry: (field_name: Type, "UCI Name", UciOptionType, default_value, include_in_uci)
+/// Each entry: (field_name: Type, "Option Name", OptionType, default_value, exposed)
 ///
 /// The macro generates:
 /// - `EngineConfig` struct with all fields as `ConfigParam<T>`
 /// - `Default` impl with specified defaults
-/// - `update_from_uci()` to set values from UCI setoption commands
-/// - `to_uci()` to send all options to the GUI
+/// - `set_option()` to set values by option name
+/// - `options()` to list the exposed options, which frontends send to the GUI
 ///
 /// The `include` flag (often `cfg!(feature = "tuning")`) controls whether
-/// the option is exposed via UCI. useful for hiding tuning params in release builds.
+/// the option is exposed. useful for hiding tuning params in release builds.
+///
+/// Value types: numbers for `Spin`, `bool` for `Check`, `String` for `String`/`Combo`
+/// and [`Button`] for `Button` (see `is_button()` for handling presses).
 macro_rules! define_config {
-    ($(($field:ident: $type:ty, $uci_name:expr, $uci_type:expr, $default:expr, $include:expr)),* $(,)?) => {
+    ($(($field:ident: $type:ty, $name:expr, $option_type:expr, $default:expr, $include:expr)),* $(,)?) => {
         #[derive(Debug, Clone)]
         pub struct EngineConfig {
             $(pub $field: ConfigParam<$type>,)*
@@ -38,33 +45,37 @@ macro_rules! define_config {
                 Self {
                     $($field: ConfigParam {
                         value: $default,
-                        uci: uci($include, $uci_name, $uci_type),
+                        option: option($include, $name, $option_type),
                     },)*
                 }
             }
         }
 
         impl EngineConfig {
-            pub fn update_from_uci(&mut self, uci_name: &str, value: &str) -> Result<(), String> {
-                // TODO: Empty name is a workaround for malformed setoption commands.
-                // Consider adding InvalidCommand variant to UciInput instead.
-                if uci_name.is_empty() {
-                    return Err("Invalid setoption command: missing option name".to_string());
+            pub fn set_option(&mut self, name: &str, value: &str) -> Result<(), String> {
+                match name {
+                    $($name if $include => self.$field.set(value),)*
+                    _ => Err(format!("Unknown option: {}", name)),
                 }
+            }
 
-                match uci_name {
-                    $($uci_name if $include => self.$field.update_from_uci(value),)*
-                    _ => Err(format!("Unknown option: {}", uci_name)),
+            /// Whether the option is a button, which triggers an action rather than holding a value.
+            pub fn is_button(&self, name: &str) -> bool {
+                match name {
+                    $($name if $include => matches!($option_type, OptionType::Button),)*
+                    _ => false,
                 }
             }
 
-            pub fn to_uci(&self, output: &std::sync::mpsc::Sender<UciOutput>) -> Result<(), std::sync::mpsc::SendError<UciOutput>> {
+            /// Exposed options with their current values, in declaration order.
+            pub fn options(&self) -> Vec<(EngineOption, String)> {
+                let mut options = Vec::new();
                 $(
-                    if self.$field.uci.is_some() {
-                        output.send(UciOutput::Option(self.$field.to_uci()))?;
+                    if let Some(option) = &self.$field.option {
+                        options.push((option.clone(), self.$field.value.to_string()));
                     }
                 )*
-                Ok(())
+                options
             }
 
         }
@@ -72,131 +83,158 @@ macro_rules! define_config {
 }
 
 // Engine configuration parameters.
-// Format: (field, "UCI Name", type, default, exposed_via_uci)
+// Format: (field, "Option Name", type, default, exposed)
 // Most tuning params use cfg!(feature = "tuning") so they're hidden in release builds.
 define_config!(
     // --- Core UCI options (always exposed) ---
-    (hash_size: i32, "Hash", UciOptionType::Spin { min: 1, max: 16384 }, 256, true),
-    (move_overhead: i32, "Move Overhead", UciOptionType::Spin { min: 0, max: 5000 }, 20, true),
-    (nnue: bool, "NNUE", UciOptionType::Check, true, true),
-
-    (aspiration_window_size: i16, "Aspiration Window Size", UciOptionType::Spin { min: 10, max: 100 }, 40, cfg!(feature = "tuning")),
-    (aspiration_window_widen: i16, "Aspiration Window Widening", UciOptionType::Spin { min: 2, max: 4 }, 2, cfg!(feature = "tuning")),
-    (aspiration_window_depth: u8, "Aspiration Window Depth", UciOptionType::Spin { min: 1, max: 10 }, 5, cfg!(feature = "tuning")),
-    (aspiration_window_retries: i16, "Aspiration Window Retries", UciOptionType::Spin { min: 1, max: 5 }, 3, cfg!(feature = "tuning")),
-
-    (history_max_value: i32, "History Max Value", UciOptionType::Spin { min: 128, max: 1024 }, 482, cfg!(feature = "tuning")),
-    (history_reduction_threshold: i16, "History Reduction Threshold", UciOptionType::Spin { min: -512, max: 512 }, -12, cfg!(feature = "tuning")),
-    (history_prune_threshold: i16, "History Prune Threshold", UciOptionType::Spin { min: -512, max: 512 }, -66, cfg!(feature = "tuning")),
-    (history_min_move_index: i32, "History Min Move Index", UciOptionType::Spin { min: 1, max: 10 }, 5, cfg!(feature = "tuning")),
-    (history_bonus_multiplier: i32, "History Bonus Multiplier", UciOptionType::Spin { min: 0, max: 30 }, 13, cfg!(feature = "tuning")),
-    (history_malus_multiplier: i32, "History Malus Multiplier", UciOptionType::Spin { min: 0, max: 30 }, 7, cfg!(feature = "tuning")),
-
-    (capture_history_max_value: i32, "Capture History Max Value", UciOptionType::Spin { min: 128, max: 1024 }, 512, cfg!(feature = "tuning")),
-    (capture_history_bonus_multiplier: i32, "Capture History Bonus Multiplier", UciOptionType::Spin { min: 0, max: 30 }, 10, cfg!(feature = "tuning")),
-    (capture_history_malus_multiplier: i32, "Capture History Malus Multiplier", UciOptionType::Spin { min: 0, max: 30 }, 3, cfg!(feature = "tuning")),
-
-    (continuation_max_value: i32, "Continuation Max Value", UciOptionType::Spin { min: 128, max: 1024 }, 512, cfg!(feature = "tuning")),
-    (continuation_max_moves: usize, "Continuation Max Moves", UciOptionType::Spin { min: 1, max: 4 }, 4, cfg!(feature = "tuning")),
-    (continuation_bonus_multiplier: i32, "Continuation Bonus Multiplier", UciOptionType::Spin { min: 0, max: 30 }, 8, cfg!(feature = "tuning")),
-    (continuation_malus_multiplier: i32, "Continuation Malus Multiplier", UciOptionType::Spin { min: 0, max: 30 }, 11, cfg!(feature = "tuning")),
-
-    (quiet_check_bonus: i16, "Quiet Check Bonus", UciOptionType::Spin { min: 0, max: 2000 }, 980, cfg!(feature = "tuning")),
-
-    (lmr_min_depth: u8, "LMR Min Depth", UciOptionType::Spin { min: 1, max: 10 }, 3, cfg!(feature = "tuning")),
-    (lmr_divisor: i32, "LMR Divisor", UciOptionType::Spin { min: 100, max: 400 }, 230, cfg!(feature = "tuning")),
-    (lmr_max_reduction_ratio: i32, "LMR Max Reduction Ratio", UciOptionType::Spin { min: 10, max: 100 }, 52, cfg!(feature = "tuning")),
-
-    (nmp_min_depth: u8, "NMP Min Depth", UciOptionType::Spin { min: 2, max: 10 }, 4, cfg!(feature = "tuning")),
-    (nmp_base_reduction: u8, "NMP Base Reduction", UciOptionType::Spin { min: 1, max: 10 }, 2, cfg!(feature = "tuning")),
-    (nmp_depth_divisor: u8, "NMP Depth Divisor", UciOptionType::Spin { min: 1, max: 10 }, 3, cfg!(feature = "tuning")),
-    (nmp_eval_margin: i16, "NMP Eval Margin", UciOptionType::Spin { min: 0, max: 500 }, 189, cfg!(feature = "tuning")),
-
-    (lmp_max_depth: u8, "LMP Max Depth", UciOptionType::Spin { min: 0, max: 20 }, 8, cfg!(feature = "tuning")),
-    (lmp_base_moves: i32, "LMP Base Moves", UciOptionType::Spin { min: 1, max: 10 }, 3, cfg!(feature = "tuning")),
-    (lmp_depth_multiplier: i32, "LMP Depth Multiplier", UciOptionType::Spin { min: 1, max: 10 }, 3, cfg!(feature = "tuning")),
-    (lmp_improving_reduction: i32, "LMP Improving Reduction", UciOptionType::Spin { min: 50, max: 100 }, 85, cfg!(feature = "tuning")),
-
-    (futility_max_depth: u8, "Futility Max Depth", UciOptionType::Spin { min: 1, max: 10 }, 4, cfg!(feature = "tuning")),
-    (futility_base_margin: i16, "Futility Base Margin", UciOptionType::Spin { min: 10, max: 300 }, 145, cfg!(feature = "tuning")),
-    (futility_depth_multiplier: i16, "Futility Depth Multiplier", UciOptionType::Spin { min: 10, max: 200 }, 97, cfg!(feature = "tuning")),
-
-    (rfp_max_depth: u8, "RFP Max Depth", UciOptionType::Spin { min: 1, max: 10 }, 5, cfg!(feature = "tuning")),
-    (rfp_base_margin: i16, "RFP Base Margin", UciOptionType::Spin { min: 10, max: 300 }, 116, cfg!(feature = "tuning")),
-    (rfp_depth_multiplier: i16, "RFP Depth Multiplier", UciOptionType::Spin { min: 10, max: 200 }, 99, cfg!(feature = "tuning")),
-    (rfp_improving_bonus: i16, "RFP Improving Bonus", UciOptionType::Spin { min: 10, max: 100 }, 50, cfg!(feature = "tuning")),
-
-    (razor_max_depth: u8, "Razor Max Depth", UciOptionType::Spin { min: 1, max: 5 }, 3, cfg!(feature = "tuning")),
-    (razor_base_margin: i16, "Razor Base Margin", UciOptionType::Spin { min: 100, max: 800 }, 512, cfg!(feature = "tuning")),
-    (razor_depth_coefficient: i16, "Razor Depth Coefficient", UciOptionType::Spin { min: 100, max: 500 }, 293, cfg!(feature = "tuning")),
-
-    (qs_delta_margin: i16, "QS Delta Margin", UciOptionType::Spin { min: 10, max: 500 }, 200, cfg!(feature = "tuning")),
-    (qs_delta_material_threshold: i16, "QS Delta Material Threshold", UciOptionType::Spin { min: 100, max: 3000 }, 1500, cfg!(feature = "tuning")),
-
-    (iid_reduction: u8, "IID Reduction", UciOptionType::Spin { min: 1, max: 10 }, 3, cfg!(feature = "tuning")),
-
-    (iir_reduction: u8, "IIR Reduction", UciOptionType::Spin { min: 0, max: 4 }, 1, cfg!(feature = "tuning")),
-    (iir_min_depth: u8, "IIR Min Depth", UciOptionType::Spin { min: 2, max: 10 }, 4, cfg!(feature = "tuning")),
+    (hash_size: i32, "Hash", OptionType::Spin { min: 1, max: 16384 }, 256, true),
+    (move_overhead: i32, "Move Overhead", OptionType::Spin { min: 0, max: 5000 }, 20, true),
+    (nnue: bool, "NNUE", OptionType::Check, true, true),
+    (threads: usize, "Threads", OptionType::Spin { min: 1, max: 1024 }, 1, true),
+    (multi_pv: usize, "MultiPV", OptionType::Spin { min: 1, max: 256 }, 1, true),
+    (ponder: bool, "Ponder", OptionType::Check, false, true),
+    (show_wdl: bool, "UCI_ShowWDL", OptionType::Check, false, true),
+    (chess960: bool, "UCI_Chess960", OptionType::Check, false, true),
+    (clear_hash: Button, "Clear Hash", OptionType::Button, Button, true),
+    (eval_file: String, "EvalFile", OptionType::String, String::new(), true),
+    (skill_level: i32, "Skill Level", OptionType::Spin { min: 0, max: MAX_SKILL_LEVEL }, MAX_SKILL_LEVEL, true),
+
+    (aspiration_window_size: i16, "Aspiration Window Size", OptionType::Spin { min: 10, max: 100 }, 40, cfg!(feature = "tuning")),
+    (aspiration_window_widen: i16, "Aspiration Window Widening", OptionType::Spin { min: 2, max: 4 }, 2, cfg!(feature = "tuning")),
+    (aspiration_window_depth: u8, "Aspiration Window Depth", OptionType::Spin { min: 1, max: 10 }, 5, cfg!(feature = "tuning")),
+    (aspiration_window_retries: i16, "Aspiration Window Retries", OptionType::Spin { min: 1, max: 5 }, 3, cfg!(feature = "tuning")),
+
+    (history_max_value: i32, "History Max Value", OptionType::Spin { min: 128, max: 1024 }, 482, cfg!(feature = "tuning")),
+    (history_reduction_threshold: i16, "History Reduction Threshold", OptionType::Spin { min: -512, max: 512 }, -12, cfg!(feature = "tuning")),
+    (history_prune_threshold: i16, "History Prune Threshold", OptionType::Spin { min: -512, max: 512 }, -66, cfg!(feature = "tuning")),
+    (history_min_move_index: i32, "History Min Move Index", OptionType::Spin { min: 1, max: 10 }, 5, cfg!(feature = "tuning")),
+    (history_bonus_multiplier: i32, "History Bonus Multiplier", OptionType::Spin { min: 0, max: 30 }, 13, cfg!(feature = "tuning")),
+    (history_malus_multiplier: i32, "History Malus Multiplier", OptionType::Spin { min: 0, max: 30 }, 7, cfg!(feature = "tuning")),
+
+    (capture_history_max_value: i32, "Capture History Max Value", OptionType::Spin { min: 128, max: 1024 }, 512, cfg!(feature = "tuning")),
+    (capture_history_bonus_multiplier: i32, "Capture History Bonus Multiplier", OptionType::Spin { min: 0, max: 30 }, 10, cfg!(feature = "tuning")),
+    (capture_history_malus_multiplier: i32, "Capture History Malus Multiplier", OptionType::Spin { min: 0, max: 30 }, 3, cfg!(feature = "tuning")),
+
+    (continuation_max_value: i32, "Continuation Max Value", OptionType::Spin { min: 128, max: 1024 }, 512, cfg!(feature = "tuning")),
+    (continuation_max_moves: usize, "Continuation Max Moves", OptionType::Spin { min: 1, max: 4 }, 4, cfg!(feature = "tuning")),
+    (continuation_bonus_multiplier: i32, "Continuation Bonus Multiplier", OptionType::Spin { min: 0, max: 30 }, 8, cfg!(feature = "tuning")),
+    (continuation_malus_multiplier: i32, "Continuation Malus Multiplier", OptionType::Spin { min: 0, max: 30 }, 11, cfg!(feature = "tuning")),
+
+    (correction_history_pawn_weight: i32, "Correction History Pawn Weight", OptionType::Spin { min: 0, max: 200 }, 100, cfg!(feature = "tuning")),
+    (correction_history_non_pawn_weight: i32, "Correction History Non-Pawn Weight", OptionType::Spin { min: 0, max: 200 }, 50, cfg!(feature = "tuning")),
+
+    (quiet_check_bonus: i16, "Quiet Check Bonus", OptionType::Spin { min: 0, max: 2000 }, 980, cfg!(feature = "tuning")),
+
+    (lmr_min_depth: u8, "LMR Min Depth", OptionType::Spin { min: 1, max: 10 }, 3, cfg!(feature = "tuning")),
+    (lmr_divisor: i32, "LMR Divisor", OptionType::Spin { min: 100, max: 400 }, 230, cfg!(feature = "tuning")),
+    (lmr_max_reduction_ratio: i32, "LMR Max Reduction Ratio", OptionType::Spin { min: 10, max: 100 }, 52, cfg!(feature = "tuning")),
+
+    (nmp_min_depth: u8, "NMP Min Depth", OptionType::Spin { min: 2, max: 10 }, 4, cfg!(feature = "tuning")),
+    (nmp_base_reduction: u8, "NMP Base Reduction", OptionType::Spin { min: 1, max: 10 }, 2, cfg!(feature = "tuning")),
+    (nmp_depth_divisor: u8, "NMP Depth Divisor", OptionType::Spin { min: 1, max: 10 }, 3, cfg!(feature = "tuning")),
+    (nmp_eval_margin: i16, "NMP Eval Margin", OptionType::Spin { min: 0, max: 500 }, 189, cfg!(feature = "tuning")),
+
+    (lmp_max_depth: u8, "LMP Max Depth", OptionType::Spin { min: 0, max: 20 }, 8, cfg!(feature = "tuning")),
+    (lmp_base_moves: i32, "LMP Base Moves", OptionType::Spin { min: 1, max: 10 }, 3, cfg!(feature = "tuning")),
+    (lmp_depth_multiplier: i32, "LMP Depth Multiplier", OptionType::Spin { min: 1, max: 10 }, 3, cfg!(feature = "tuning")),
+    (lmp_improving_reduction: i32, "LMP Improving Reduction", OptionType::Spin { min: 50, max: 100 }, 85, cfg!(feature = "tuning")),
+
+    (futility_max_depth: u8, "Futility Max Depth", OptionType::Spin { min: 1, max: 10 }, 4, cfg!(feature = "tuning")),
+    (futility_base_margin: i16, "Futility Base Margin", OptionType::Spin { min: 10, max: 300 }, 145, cfg!(feature = "tuning")),
+    (futility_depth_multiplier: i16, "Futility Depth Multiplier", OptionType::Spin { min: 10, max: 200 }, 97, cfg!(feature = "tuning")),
+
+    (rfp_max_depth: u8, "RFP Max Depth", OptionType::Spin { min: 1, max: 10 }, 5, cfg!(feature = "tuning")),
+    (rfp_base_margin: i16, "RFP Base Margin", OptionType::Spin { min: 10, max: 300 }, 116, cfg!(feature = "tuning")),
+    (rfp_depth_multiplier: i16, "RFP Depth Multiplier", OptionType::Spin { min: 10, max: 200 }, 99, cfg!(feature = "tuning")),
+    (rfp_improving_bonus: i16, "RFP Improving Bonus", OptionType::Spin { min: 10, max: 100 }, 50, cfg!(feature = "tuning")),
+
+    (probcut_min_depth: u8, "ProbCut Min Depth", OptionType::Spin { min: 3, max: 10 }, 5, cfg!(feature = "tuning")),
+    (probcut_margin: i16, "ProbCut Margin", OptionType::Spin { min: 50, max: 400 }, 200, cfg!(feature = "tuning")),
+    (probcut_reduction: u8, "ProbCut Reduction", OptionType::Spin { min: 2, max: 6 }, 4, cfg!(feature = "tuning")),
+
+    (razor_max_depth: u8, "Razor Max Depth", OptionType::Spin { min: 1, max: 5 }, 3, cfg!(feature = "tuning")),
+    (razor_base_margin: i16, "Razor Base Margin", OptionType::Spin { min: 100, max: 800 }, 512, cfg!(feature = "tuning")),
+    (razor_depth_coefficient: i16, "Razor Depth Coefficient", OptionType::Spin { min: 100, max: 500 }, 293, cfg!(feature = "tuning")),
+
+    (qs_delta_margin: i16, "QS Delta Margin", OptionType::Spin { min: 10, max: 500 }, 200, cfg!(feature = "tuning")),
+    (qs_delta_material_threshold: i16, "QS Delta Material Threshold", OptionType::Spin { min: 100, max: 3000 }, 1500, cfg!(feature = "tuning")),
+
+    (iid_reduction: u8, "IID Reduction", OptionType::Spin { min: 1, max: 10 }, 3, cfg!(feature = "tuning")),
+
+    (iir_reduction: u8, "IIR Reduction", OptionType::Spin { min: 0, max: 4 }, 1, cfg!(feature = "tuning")),
+    (iir_min_depth: u8, "IIR Min Depth", OptionType::Spin { min: 2, max: 10 }, 4, cfg!(feature = "tuning")),
+
+    // Singular Extensions
+    (singular_min_depth: u8, "Singular Min Depth", OptionType::Spin { min: 4, max: 16 }, 8, cfg!(feature = "tuning")),
+    (singular_tt_depth_margin: u8, "Singular TT Depth Margin", OptionType::Spin { min: 0, max: 6 }, 3, cfg!(feature = "tuning")),
+    (singular_margin: i16, "Singular Margin", OptionType::Spin { min: 1, max: 10 }, 2, cfg!(feature = "tuning")),
+
+    // Extensions
+    (check_extension: u8, "Check Extension", OptionType::Spin { min: 0, max: 2 }, 1, cfg!(feature = "tuning")),
+    (recapture_extension: u8, "Recapture Extension", OptionType::Spin { min: 0, max: 2 }, 1, cfg!(feature = "tuning")),
+    (single_reply_extension: u8, "Single Reply Extension", OptionType::Spin { min: 0, max: 2 }, 1, cfg!(feature = "tuning")),
+    (passed_pawn_extension: u8, "Passed Pawn Extension", OptionType::Spin { min: 0, max: 2 }, 1, cfg!(feature = "tuning")),
+    (extension_budget: u8, "Extension Budget", OptionType::Spin { min: 0, max: 32 }, 16, cfg!(feature = "tuning")),
 
     // SEE Pruning
-    (see_prune_min_remaining_depth: u8, "SEE Prune Min Remaining Depth", UciOptionType::Spin { min: 0, max: 10 }, 1, cfg!(feature = "tuning")),
-    (see_prune_max_depth: u8, "SEE Prune Max Depth", UciOptionType::Spin { min: 1, max: 10 }, 6, cfg!(feature = "tuning")),
-    (see_prune_depth_margin: i16, "SEE Prune Depth Margin", UciOptionType::Spin { min: 10, max: 150 }, 77, cfg!(feature = "tuning")),
-    (see_prune_min_attacker_value: i16, "SEE Prune Min Attacker Value", UciOptionType::Spin { min: 0, max: 500 }, 200, cfg!(feature = "tuning")),
-
-    (piece_value_pawn_mg: f32, "Piece Value Pawn MG", UciOptionType::Spin { min: 50, max: 150 }, 98.0, cfg!(feature = "tuning")),
-    (piece_value_pawn_eg: f32, "Piece Value Pawn EG", UciOptionType::Spin { min: 50, max: 150 }, 113.0, cfg!(feature = "tuning")),
-    (piece_value_knight_mg: f32, "Piece Value Knight MG", UciOptionType::Spin { min: 250, max: 400 }, 325.0, cfg!(feature = "tuning")),
-    (piece_value_knight_eg: f32, "Piece Value Knight EG", UciOptionType::Spin { min: 250, max: 400 }, 340.0, cfg!(feature = "tuning")),
-    (piece_value_bishop_mg: f32, "Piece Value Bishop MG", UciOptionType::Spin { min: 250, max: 400 }, 335.0, cfg!(feature = "tuning")),
-    (piece_value_bishop_eg: f32, "Piece Value Bishop EG", UciOptionType::Spin { min: 250, max: 400 }, 350.0, cfg!(feature = "tuning")),
-    (piece_value_rook_mg: f32, "Piece Value Rook MG", UciOptionType::Spin { min: 400, max: 600 }, 510.0, cfg!(feature = "tuning")),
-    (piece_value_rook_eg: f32, "Piece Value Rook EG", UciOptionType::Spin { min: 450, max: 650 }, 560.0, cfg!(feature = "tuning")),
-    (piece_value_queen_mg: f32, "Piece Value Queen MG", UciOptionType::Spin { min: 800, max: 1200 }, 975.0, cfg!(feature = "tuning")),
-    (piece_value_queen_eg: f32, "Piece Value Queen EG", UciOptionType::Spin { min: 800, max: 1300 }, 1020.0, cfg!(feature = "tuning")),
-
-    (hce_tempo_bonus: i16, "HCE Tempo Bonus", UciOptionType::Spin { min: 0, max: 30 }, 10, cfg!(feature = "tuning")),
-
-    (hce_doubled_pawn_penalty: i16, "HCE Doubled Pawn Penalty", UciOptionType::Spin { min: 0, max: 100 }, 30, cfg!(feature = "tuning")),
-    (hce_tripled_pawn_penalty: i16, "HCE Tripled Pawn Penalty", UciOptionType::Spin { min: 0, max: 150 }, 60, cfg!(feature = "tuning")),
-    (hce_isolated_pawn_penalty: i16, "HCE Isolated Pawn Penalty", UciOptionType::Spin { min: 0, max: 100 }, 39, cfg!(feature = "tuning")),
-    (hce_backward_pawn_penalty: i16, "HCE Backward Pawn Penalty", UciOptionType::Spin { min: 0, max: 100 }, 20, cfg!(feature = "tuning")),
-    (hce_backward_pawn_half_open_penalty: i16, "HCE Backward Pawn Half Open Penalty", UciOptionType::Spin { min: 0, max: 50 }, 10, cfg!(feature = "tuning")),
-
-    (hce_passed_pawn_linear: i16, "HCE Passed Pawn Linear", UciOptionType::Spin { min: 0, max: 20 }, 6, cfg!(feature = "tuning")),
-    (hce_passed_pawn_quadratic: i16, "HCE Passed Pawn Quadratic", UciOptionType::Spin { min: 0, max: 10 }, 5, cfg!(feature = "tuning")),
-    (hce_center_pawn_bonus: i16, "HCE Center Pawn Bonus", UciOptionType::Spin { min: 0, max: 50 }, 20, cfg!(feature = "tuning")),
-
-    (hce_bishop_pair_bonus: i16, "HCE Bishop Pair Bonus", UciOptionType::Spin { min: 0, max: 150 }, 50, cfg!(feature = "tuning")),
-    (hce_rook_open_file_bonus: i16, "HCE Rook Open File Bonus", UciOptionType::Spin { min: 0, max: 50 }, 15, cfg!(feature = "tuning")),
-    (hce_rook_semi_open_file_bonus: i16, "HCE Rook Semi-Open File Bonus", UciOptionType::Spin { min: 0, max: 30 }, 10, cfg!(feature = "tuning")),
-    (hce_rook_seventh_rank_bonus: i16, "HCE Rook Seventh Rank Bonus", UciOptionType::Spin { min: 0, max: 50 }, 20, cfg!(feature = "tuning")),
-
-    (hce_space_multiplier: i16, "HCE Space Multiplier", UciOptionType::Spin { min: 0, max: 10 }, 4, cfg!(feature = "tuning")),
-
-    (hce_supported_minor_bonus: i16, "HCE Supported Minor Bonus", UciOptionType::Spin { min: 0, max: 20 }, 5, cfg!(feature = "tuning")),
-    (hce_supported_major_bonus: i16, "HCE Supported Major Bonus", UciOptionType::Spin { min: 0, max: 30 }, 10, cfg!(feature = "tuning")),
-
-    (hce_king_shield_r1_bonus: i16, "HCE King Shield R1 Bonus", UciOptionType::Spin { min: 0, max: 50 }, 12, cfg!(feature = "tuning")),
-    (hce_king_shield_r2_bonus: i16, "HCE King Shield R2 Bonus", UciOptionType::Spin { min: 0, max: 50 }, 6, cfg!(feature = "tuning")),
-
-    (hce_king_open_file_penalty: i16, "HCE King Open File Penalty", UciOptionType::Spin { min: 0, max: 50 }, 24, cfg!(feature = "tuning")),
-    (hce_king_semi_open_file_penalty: i16, "HCE King Semi Open File Penalty", UciOptionType::Spin { min: 0, max: 50 }, 12, cfg!(feature = "tuning")),
-    (hce_king_thin_cover_penalty: i16, "HCE King Thin Cover Penalty", UciOptionType::Spin { min: 0, max: 50 }, 6, cfg!(feature = "tuning")),
-
-    (hce_king_pressure_knight: i16, "HCE King Pressure Knight", UciOptionType::Spin { min: 0, max: 50 }, 12, cfg!(feature = "tuning")),
-    (hce_king_pressure_bishop: i16, "HCE King Pressure Bishop", UciOptionType::Spin { min: 0, max: 50}, 14, cfg!(feature = "tuning")),
-    (hce_king_pressure_rook: i16, "HCE King Pressure Rook", UciOptionType::Spin { min: 0, max: 50}, 18, cfg!(feature = "tuning")),
-    (hce_king_pressure_queen: i16, "HCE King Pressure Queen", UciOptionType::Spin { min: 0, max: 50 }, 22, cfg!(feature = "tuning")),
-    (hce_king_pressure_pawn: i16, "HCE King Pressure Pawn", UciOptionType::Spin { min: 0, max: 50 }, 8, cfg!(feature = "tuning")),
-
-    (hce_king_central_penalty: i16, "HCE King Central Penalty", UciOptionType::Spin { min: 0, max: 50 }, 20, cfg!(feature = "tuning")),
-    (hce_king_activity_bonus: i16, "HCE King Activity Bonus", UciOptionType::Spin { min: 0, max: 50 }, 14, cfg!(feature = "tuning")),
-
-    (hce_threats_multiplier: i16, "HCE Threats Multiplier", UciOptionType::Spin { min: 0, max: 100 }, 50, cfg!(feature = "tuning")),
-
-    (piece_repetition_base_penalty: i16, "Piece Repetition Base Penalty", UciOptionType::Spin { min: 0, max: 100 }, 10, cfg!(feature = "tuning")),
-    (piece_repetition_min_phase: f32, "Piece Repetition Min Phase", UciOptionType::Spin { min: 0, max: 100 }, 50.0, cfg!(feature = "tuning")),
+    (see_prune_min_remaining_depth: u8, "SEE Prune Min Remaining Depth", OptionType::Spin { min: 0, max: 10 }, 1, cfg!(feature = "tuning")),
+    (see_prune_max_depth: u8, "SEE Prune Max Depth", OptionType::Spin { min: 1, max: 10 }, 6, cfg!(feature = "tuning")),
+    (see_prune_depth_margin: i16, "SEE Prune Depth Margin", OptionType::Spin { min: 10, max: 150 }, 77, cfg!(feature = "tuning")),
+    (see_prune_min_attacker_value: i16, "SEE Prune Min Attacker Value", OptionType::Spin { min: 0, max: 500 }, 200, cfg!(feature = "tuning")),
+
+    (piece_value_pawn_mg: f32, "Piece Value Pawn MG", OptionType::Spin { min: 50, max: 150 }, 98.0, cfg!(feature = "tuning")),
+    (piece_value_pawn_eg: f32, "Piece Value Pawn EG", OptionType::Spin { min: 50, max: 150 }, 113.0, cfg!(feature = "tuning")),
+    (piece_value_knight_mg: f32, "Piece Value Knight MG", OptionType::Spin { min: 250, max: 400 }, 325.0, cfg!(feature = "tuning")),
+    (piece_value_knight_eg: f32, "Piece Value Knight EG", OptionType::Spin { min: 250, max: 400 }, 340.0, cfg!(feature = "tuning")),
+    (piece_value_bishop_mg: f32, "Piece Value Bishop MG", OptionType::Spin { min: 250, max: 400 }, 335.0, cfg!(feature = "tuning")),
+    (piece_value_bishop_eg: f32, "Piece Value Bishop EG", OptionType::Spin { min: 250, max: 400 }, 350.0, cfg!(feature = "tuning")),
+    (piece_value_rook_mg: f32, "Piece Value Rook MG", OptionType::Spin { min: 400, max: 600 }, 510.0, cfg!(feature = "tuning")),
+    (piece_value_rook_eg: f32, "Piece Value Rook EG", OptionType::Spin { min: 450, max: 650 }, 560.0, cfg!(feature = "tuning")),
+    (piece_value_queen_mg: f32, "Piece Value Queen MG", OptionType::Spin { min: 800, max: 1200 }, 975.0, cfg!(feature = "tuning")),
+    (piece_value_queen_eg: f32, "Piece Value Queen EG", OptionType::Spin { min: 800, max: 1300 }, 1020.0, cfg!(feature = "tuning")),
+
+    (hce_tempo_bonus: i16, "HCE Tempo Bonus", OptionType::Spin { min: 0, max: 30 }, 10, cfg!(feature = "tuning")),
+
+    (hce_doubled_pawn_penalty: i16, "HCE Doubled Pawn Penalty", OptionType::Spin { min: 0, max: 100 }, 30, cfg!(feature = "tuning")),
+    (hce_tripled_pawn_penalty: i16, "HCE Tripled Pawn Penalty", OptionType::Spin { min: 0, max: 150 }, 60, cfg!(feature = "tuning")),
+    (hce_isolated_pawn_penalty: i16, "HCE Isolated Pawn Penalty", OptionType::Spin { min: 0, max: 100 }, 39, cfg!(feature = "tuning")),
+    (hce_backward_pawn_penalty: i16, "HCE Backward Pawn Penalty", OptionType::Spin { min: 0, max: 100 }, 20, cfg!(feature = "tuning")),
+    (hce_backward_pawn_half_open_penalty: i16, "HCE Backward Pawn Half Open Penalty", OptionType::Spin { min: 0, max: 50 }, 10, cfg!(feature = "tuning")),
+
+    (hce_passed_pawn_linear: i16, "HCE Passed Pawn Linear", OptionType::Spin { min: 0, max: 20 }, 6, cfg!(feature = "tuning")),
+    (hce_passed_pawn_quadratic: i16, "HCE Passed Pawn Quadratic", OptionType::Spin { min: 0, max: 10 }, 5, cfg!(feature = "tuning")),
+    (hce_center_pawn_bonus: i16, "HCE Center Pawn Bonus", OptionType::Spin { min: 0, max: 50 }, 20, cfg!(feature = "tuning")),
+
+    (hce_bishop_pair_bonus: i16, "HCE Bishop Pair Bonus", OptionType::Spin { min: 0, max: 150 }, 50, cfg!(feature = "tuning")),
+    (hce_rook_open_file_bonus: i16, "HCE Rook Open File Bonus", OptionType::Spin { min: 0, max: 50 }, 15, cfg!(feature = "tuning")),
+    (hce_rook_semi_open_file_bonus: i16, "HCE Rook Semi-Open File Bonus", OptionType::Spin { min: 0, max: 30 }, 10, cfg!(feature = "tuning")),
+    (hce_rook_seventh_rank_bonus: i16, "HCE Rook Seventh Rank Bonus", OptionType::Spin { min: 0, max: 50 }, 20, cfg!(feature = "tuning")),
+
+    (hce_space_multiplier: i16, "HCE Space Multiplier", OptionType::Spin { min: 0, max: 10 }, 4, cfg!(feature = "tuning")),
+
+    (hce_supported_minor_bonus: i16, "HCE Supported Minor Bonus", OptionType::Spin { min: 0, max: 20 }, 5, cfg!(feature = "tuning")),
+    (hce_supported_major_bonus: i16, "HCE Supported Major Bonus", OptionType::Spin { min: 0, max: 30 }, 10, cfg!(feature = "tuning")),
+
+    (hce_king_shield_r1_bonus: i16, "HCE King Shield R1 Bonus", OptionType::Spin { min: 0, max: 50 }, 12, cfg!(feature = "tuning")),
+    (hce_king_shield_r2_bonus: i16, "HCE King Shield R2 Bonus", OptionType::Spin { min: 0, max: 50 }, 6, cfg!(feature = "tuning")),
+
+    (hce_king_open_file_penalty: i16, "HCE King Open File Penalty", OptionType::Spin { min: 0, max: 50 }, 24, cfg!(feature = "tuning")),
+    (hce_king_semi_open_file_penalty: i16, "HCE King Semi Open File Penalty", OptionType::Spin { min: 0, max: 50 }, 12, cfg!(feature = "tuning")),
+    (hce_king_thin_cover_penalty: i16, "HCE King Thin Cover Penalty", OptionType::Spin { min: 0, max: 50 }, 6, cfg!(feature = "tuning")),
+
+    (hce_king_pressure_knight: i16, "HCE King Pressure Knight", OptionType::Spin { min: 0, max: 50 }, 12, cfg!(feature = "tuning")),
+    (hce_king_pressure_bishop: i16, "HCE King Pressure Bishop", OptionType::Spin { min: 0, max: 50}, 14, cfg!(feature = "tuning")),
+    (hce_king_pressure_rook: i16, "HCE King Pressure Rook", OptionType::Spin { min: 0, max: 50}, 18, cfg!(feature = "tuning")),
+    (hce_king_pressure_queen: i16, "HCE King Pressure Queen", OptionType::Spin { min: 0, max: 50 }, 22, cfg!(feature = "tuning")),
+    (hce_king_pressure_pawn: i16, "HCE King Pressure Pawn", OptionType::Spin { min: 0, max: 50 }, 8, cfg!(feature = "tuning")),
+
+    (hce_king_central_penalty: i16, "HCE King Central Penalty", OptionType::Spin { min: 0, max: 50 }, 20, cfg!(feature = "tuning")),
+    (hce_king_activity_bonus: i16, "HCE King Activity Bonus", OptionType::Spin { min: 0, max: 50 }, 14, cfg!(feature = "tuning")),
+
+    (hce_threats_multiplier: i16, "HCE Threats Multiplier", OptionType::Spin { min: 0, max: 100 }, 50, cfg!(feature = "tuning")),
+
+    (piece_repetition_base_penalty: i16, "Piece Repetition Base Penalty", OptionType::Spin { min: 0, max: 100 }, 10, cfg!(feature = "tuning")),
+    (piece_repetition_min_phase: f32, "Piece Repetition Min Phase", OptionType::Spin { min: 0, max: 100 }, 50.0, cfg!(feature = "tuning")),
 );
 
 impl EngineConfig {
@@ -215,6 +253,16 @@ impl EngineConfig {
         }
     }
 
+    pub(crate) fn get_extension_config(&self) -> ExtensionConfig {
+        ExtensionConfig {
+            check: self.check_extension.value,
+            recapture: self.recapture_extension.value,
+            single_reply: self.single_reply_extension.value,
+            passed_pawn: self.passed_pawn_extension.value,
+            budget: self.extension_budget.value,
+        }
+    }
+
     pub fn get_hce_config(&self) -> HCEConfig {
         HCEConfig {
             tempo_bonus: self.hce_tempo_bonus.value,
@@ -256,12 +304,12 @@ impl EngineConfig {
     }
 }
 
-/// A configuration parameter with optional UCI metadata.
-/// If `uci` is Some, the parameter can be changed via UCI setoption.
+/// A configuration parameter with optional option metadata.
+/// If `option` is Some, frontends expose it and the GUI can change it.
 #[derive(Debug, Clone)]
 pub struct ConfigParam<T> {
     pub value: T,
-    pub uci: Option<UciOption>,
+    pub option: Option<EngineOption>,
 }
 
 impl<T> ConfigParam<T>
@@ -269,9 +317,9 @@ where
     T: FromStr + ToString + Clone,
     T::Err: std::fmt::Display,
 {
-    pub fn update_from_uci(&mut self, value: &str) -> Result<(), String> {
-        if let Some(uci_meta) = &self.uci {
-            uci_meta.option_type.validate(value)?;
+    pub fn set(&mut self, value: &str) -> Result<(), String> {
+        if let Some(option) = &self.option {
+            option.option_type.validate(value)?;
         }
 
         let new_value = value
@@ -282,11 +330,26 @@ where
         Ok(())
     }
 
-    pub fn to_uci(&self) -> String {
-        let uci_meta = self
-            .uci
-            .as_ref()
-            .expect("UCI metadata required for UCI output");
-        uci_meta.option_type.to_uci(uci_meta.name, &self.value)
+    /// The option name, if the parameter is exposed.
+    pub fn option_name(&self) -> Option<&'static str> {
+        self.option.as_ref().map(|option| option.name)
+    }
+}
+
+/// Value of a button option. Buttons hold no state - pressing one triggers an action.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Button;
+
+impl FromStr for Button {
+    type Err = Infallible;
+
+    fn from_str(_: &str) -> Result<Self, Self::Err> {
+        Ok(Button)
+    }
+}
+
+impl fmt::Display for Button {
+    fn fmt(&self, _: &mut fmt::Formatter) -> fmt::Result {
+        Ok(())
     }
 }
@@ -1,37 +1,192 @@
-use utils::{cap_eval_by_material, Position};
+use std::fmt;
+
+use cozy_chess::Board;
+use hce::EvalTrace;
+use utils::{cap_eval_by_material, flip_eval_perspective, game_phase, Position};
 
 use super::Engine;
+use crate::stack::{SearchNode, SearchStack};
+
+/// How the static evaluation of the root position is put together (the "eval" command).
+/// All scores are from White's perspective.
+pub struct EvalReport {
+    /// Hand-crafted evaluation, term by term
+    pub trace: EvalTrace,
+    /// Neural network evaluation, if a network is loaded
+    pub nnue: Option<i16>,
+    /// Name of the evaluation the search uses
+    pub eval_name: String,
+    /// Search-level piece repetition penalty (subtracted)
+    pub repetition_penalty: i16,
+    /// Learned correction for systematic eval errors (added)
+    pub correction: i16,
+    /// Change made by `cap_eval_by_material` for sides that can't win
+    pub material_cap: i16,
+    /// The evaluation as seen by the search
+    pub eval: i16,
+}
 
 impl Engine {
+    /// Breaks down the static evaluation of the current position.
+    pub fn eval_report(&mut self) -> EvalReport {
+        let board = self.board.clone();
+        let position = Position::new(&board);
+        let phase = game_phase(&board);
+
+        let tracer = hce::Evaluator::new(self.piece_values, self.config.get_hce_config());
+        let trace = tracer.trace(&position, phase);
+        let nnue = self.nnue.as_mut().map(|nnue| nnue.evaluate(&board));
+
+        // Penalties look at the search path, which is just the root here
+        let mut root_stack = SearchStack::with_capacity(1);
+        root_stack.push(SearchNode::new(board.hash()));
+
+        let raw = match nnue {
+            Some(score) if self.config.nnue.value => score,
+            _ => trace.total(),
+        };
+        let penalized = self.apply_penalties(raw, phase, &root_stack);
+        let correction =
+            flip_eval_perspective(board.side_to_move(), self.correction_history.get(&board));
+        let corrected = penalized.saturating_add(correction);
+        let eval = cap_eval_by_material(&board, corrected);
+
+        EvalReport {
+            trace,
+            nnue,
+            eval_name: self.eval_name(),
+            repetition_penalty: raw - penalized,
+            correction,
+            material_cap: eval - corrected,
+            eval,
+        }
+    }
+
     pub(super) fn eval(&mut self, position: &Position, phase: f32) -> i16 {
-        let mut score = if self.config.nnue.value && self.nnue.is_some() {
-            self.nnue.as_mut().unwrap().evaluate(position.board)
-        } else {
-            self.hce.evaluate(position, phase)
+        let stm = position.board.side_to_move();
+        let raw = flip_eval_perspective(stm, self.raw_eval(position, phase));
+        flip_eval_perspective(stm, self.corrected_eval(position.board, raw))
+    }
+
+    /// Static eval before the correction history, from White's perspective.
+    /// This is what the transposition table caches, as corrections keep changing.
+    pub(super) fn raw_eval(&mut self, position: &Position, phase: f32) -> i16 {
+        let score = match self.nnue.as_mut() {
+            Some(nnue) if self.config.nnue.value => nnue.evaluate(position.board),
+            _ => self.hce.evaluate(position, phase),
         };
 
-        score = self.apply_penalties(score, phase);
-        score = cap_eval_by_material(position.board, score);
+        self.apply_penalties(score, phase, &self.search_stack)
+    }
 
-        score
+    /// Applies the correction history and the material cap to a raw eval.
+    /// Both scores are from the side to move's perspective.
+    pub(super) fn corrected_eval(&self, board: &Board, raw_eval: i16) -> i16 {
+        let corrected = raw_eval.saturating_add(self.correction_history.get(board));
+        let stm = board.side_to_move();
+        flip_eval_perspective(
+            stm,
+            cap_eval_by_material(board, flip_eval_perspective(stm, corrected)),
+        )
     }
 
-    fn apply_penalties(&self, score: i16, phase: f32) -> i16 {
+    /// Name of the evaluation currently in use.
+    pub(super) fn eval_name(&self) -> String {
+        match &self.nnue {
+            Some(nnue) if self.config.nnue.value => nnue.name(),
+            _ => self.hce.name(),
+        }
+    }
+
+    fn apply_penalties(&self, score: i16, phase: f32, stack: &SearchStack) -> i16 {
         let mut adjusted_score = score;
 
         // Piece repetition penalty (opening/middlegame)
         let min_phase = self.config.piece_repetition_min_phase.value / 100.0;
         if phase > min_phase {
             let normalized_phase = (phase - min_phase) / (1.0 - min_phase);
-            let penalty = self.piece_repetition_penalty();
+            let base_penalty = self.config.piece_repetition_base_penalty.value;
+            let penalty = stack.piece_repetition_penalty(base_penalty);
             adjusted_score -= ((penalty as f32) * normalized_phase).round() as i16;
         }
 
         adjusted_score
     }
+}
+
+impl fmt::Display for EvalReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.trace)?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "HCE evaluation      {:>6} (white side)",
+            self.trace.total()
+        )?;
+        match self.nnue {
+            Some(score) => writeln!(f, "NNUE evaluation     {:>6} (white side)", score)?,
+            None => writeln!(f, "NNUE evaluation       none")?,
+        }
+        writeln!(f, "Repetition penalty  {:>6}", -self.repetition_penalty)?;
+        writeln!(f, "Correction history  {:>6}", self.correction)?;
+        writeln!(f, "Material cap        {:>6}", self.material_cap)?;
+        write!(
+            f,
+            "Final evaluation    {:>6} (white side) [{}]",
+            self.eval, self.eval_name
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{atomic::AtomicBool, Arc};
+
+    use cozy_chess::Board;
 
-    fn piece_repetition_penalty(&self) -> i16 {
-        let base_penalty = self.config.piece_repetition_base_penalty.value;
-        self.search_stack.piece_repetition_penalty(base_penalty)
+    use super::*;
+    use crate::EngineConfig;
+
+    #[test]
+    fn test_eval_report_matches_eval() {
+        let fens = [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 b - - 0 1",
+            "8/8/4k3/8/8/3NK3/8/8 w - - 0 1",
+        ];
+
+        let mut config = EngineConfig::default();
+        config.hash_size.value = 1;
+        let hce = Box::new(hce::Evaluator::new(
+            config.get_piece_values(),
+            config.get_hce_config(),
+        ));
+        let mut engine = Engine::new(&config, hce, None, Arc::new(AtomicBool::new(false)));
+
+        for fen in fens {
+            let board: Board = fen.parse().unwrap();
+            engine.set_position(board.clone(), None);
+
+            // Reporting must leave the search path alone
+            engine.search_stack.clear();
+            engine.search_stack.push(SearchNode::new(0));
+            engine.search_stack.push(SearchNode::new(1));
+            let report = engine.eval_report();
+            assert_eq!(engine.search_stack.as_slice().len(), 2, "{}", fen);
+
+            let position = Position::new(&board);
+            let phase = game_phase(&board);
+            engine.search_stack.clear();
+            engine.search_stack.push(SearchNode::new(board.hash()));
+
+            assert_eq!(
+                report.trace.total(),
+                engine.hce.evaluate(&position, phase),
+                "{}",
+                fen
+            );
+            assert_eq!(report.eval, engine.eval(&position, phase), "{}", fen);
+        }
     }
 }
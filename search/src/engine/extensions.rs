@@ -0,0 +1,59 @@
+use cozy_chess::{Board, Move};
+
+use crate::extensions::{
+    can_singular_extend, classify, singular_beta, verification_depth, Singularity,
+};
+use crate::transposition::Bound;
+
+use super::Engine;
+
+impl Engine {
+    /// Singular extensions: search all moves except the TT move at reduced depth
+    /// against a bound just below the TT value. If none of them comes close,
+    /// the TT move is singular and worth searching deeper.
+    ///
+    /// <https://www.chessprogramming.org/Singular_Extensions>
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn try_singular_extension(
+        &mut self,
+        board: &Board,
+        depth: u8,
+        beta: i16,
+        remaining_depth: u8,
+        tt_move: Move,
+        tt_value: i16,
+        tt_bound: Bound,
+        tt_depth: u8,
+    ) -> Option<Singularity> {
+        if !can_singular_extend(
+            remaining_depth,
+            tt_depth,
+            tt_bound,
+            tt_value,
+            self.config.singular_min_depth.value,
+            self.config.singular_tt_depth_margin.value,
+        ) {
+            return None;
+        }
+
+        let singular_beta =
+            singular_beta(tt_value, remaining_depth, self.config.singular_margin.value);
+
+        // Null window search of the same node without the TT move
+        self.search_stack.set_excluded_move(Some(tt_move));
+        let value = self.search_subtree(
+            board,
+            depth,
+            depth + verification_depth(remaining_depth),
+            singular_beta - 1,
+            singular_beta,
+            false,
+            false,
+        );
+        self.search_stack.set_excluded_move(None);
+        // The line found without the TT move must not end up in the PV
+        self.pv_table.clear(depth);
+
+        Some(classify(value, singular_beta, beta))
+    }
+}
@@ -1,28 +1,38 @@
 use std::sync::{
-    atomic::{AtomicBool, Ordering},
-    mpsc::Sender,
+    atomic::{AtomicBool, AtomicU64, Ordering},
     Arc,
 };
+use std::time::{Duration, Instant};
 
-use ahash::AHashSet;
 use cozy_chess::{Board, Move};
-use evaluation::{PieceValues, HCE, NNUE};
-use uci::{commands::Info, pv_to_uci, UciOutput};
+use evaluation::{wdl::material_count, PieceValues, HCE, NNUE};
+use utils::GameHistory;
 
 use crate::{
-    history::{CaptureHistory, ContinuationHistory, HistoryHeuristic},
+    history::{CaptureHistory, ContinuationHistory, CorrectionHistory, HistoryHeuristic},
     stack::SearchStack,
+    time_control::Pondering,
     transposition::{QSTable, TranspositionTable},
-    utils::{convert_centipawn_score, convert_mate_score},
-    EngineConfig,
+    utils::{convert_centipawn_score, convert_mate_score, score_to_wdl},
+    EngineConfig, ScoreBound, SearchInfo, SearchObserver,
 };
 
 mod eval;
+mod extensions;
+mod multipv;
 mod pruning;
+mod pv_table;
 mod quiescence;
 mod search;
+mod skill;
+mod threads;
 
 use crate::MAX_DEPTH;
+pub use eval::EvalReport;
+use multipv::PvLine;
+use pv_table::PvTable;
+use skill::Skill;
+pub use skill::MAX_SKILL_LEVEL;
 
 pub struct Engine {
     /// Configuration for the engine
@@ -30,6 +40,8 @@ pub struct Engine {
 
     /// Signal to terminate search (time control or UCI stop)
     stop: Arc<AtomicBool>,
+    /// Active while searching the predicted position ("go ponder"), ended on ponderhit or stop
+    pondering: Pondering,
 
     /// Piece values for the engine
     piece_values: PieceValues,
@@ -41,20 +53,47 @@ pub struct Engine {
 
     /// The position we are finding the best move for (root position)
     board: Board,
-    /// Position hashes for repetition detection - all positions up until the search.
-    game_history: AHashSet<u64>,
+    /// Positions played before the root, for repetition detection.
+    game_history: GameHistory,
 
-    /// Number of nodes searched
-    nodes: u32,
+    /// Number of nodes searched by this thread (readable by the main thread while searching)
+    nodes: Arc<AtomicU64>,
     /// Principal variation - the current best line we have found
     current_pv: Vec<Move>,
+    /// Best lines from each ply of the current search path
+    pv_table: PvTable,
+    /// Best lines of the last completed iteration, best first (one per MultiPV line)
+    pv_lines: Vec<PvLine>,
     /// Selective depth (max ply reached including quiescence - deepest we have gotten)
     max_depth_reached: u8,
-
-    /// Main transposition table
-    tt: TranspositionTable,
-    /// Quiescence search transposition table
-    qs_tt: QSTable,
+    /// Root moves to consider ("go searchmoves"). Empty means all legal moves.
+    search_moves: Vec<Move>,
+    /// Total node budget ("go nodes"), enforced by the main thread only
+    node_limit: Option<u64>,
+    /// When the current search started (for periodic current move reports)
+    search_start: Instant,
+
+    /// Reduced playing strength, `None` at full strength
+    skill: Option<Skill>,
+
+    /// Debug mode - report extra diagnostics as observer messages
+    debug: bool,
+    /// Transposition table probes and hits in the current search (for debug output)
+    tt_probes: u64,
+    tt_hits: u64,
+
+    /// Main transposition table (shared between all search threads)
+    tt: Arc<TranspositionTable>,
+    /// Quiescence search transposition table (shared between all search threads)
+    qs_tt: Arc<QSTable>,
+
+    /// Search thread index. 0 is the main thread, which owns the helpers.
+    thread_id: usize,
+    /// Lazy SMP helper searchers, each run on its own thread during search.
+    /// <https://www.chessprogramming.org/Lazy_SMP>
+    helpers: Vec<Engine>,
+    /// Node counters of the helpers, so the total stays readable while they search
+    helper_nodes: Vec<Arc<AtomicU64>>,
 
     /// Tracks active search path - used for repetition, improving, etc.
     search_stack: SearchStack,
@@ -68,6 +107,8 @@ pub struct Engine {
     capture_history: CaptureHistory,
     /// Scores based on move sequences
     continuation_history: Box<ContinuationHistory>,
+    /// Learned static eval errors, added to the static eval
+    correction_history: CorrectionHistory,
 }
 
 impl Engine {
@@ -77,34 +118,67 @@ impl Engine {
         nnue: Option<Box<dyn NNUE>>,
         stop: Arc<AtomicBool>,
     ) -> Self {
-        let mut instance = Self {
+        let tt = Arc::new(TranspositionTable::new(1));
+        let qs_tt = Arc::new(QSTable::new(1));
+
+        let mut instance = Self::with_tables(config, hce, nnue, stop, tt, qs_tt, 0);
+
+        instance.configure(config, true);
+
+        instance
+    }
+
+    /// Creates an engine around existing (possibly shared) transposition tables.
+    fn with_tables(
+        config: &EngineConfig,
+        hce: Box<dyn HCE>,
+        nnue: Option<Box<dyn NNUE>>,
+        stop: Arc<AtomicBool>,
+        tt: Arc<TranspositionTable>,
+        qs_tt: Arc<QSTable>,
+        thread_id: usize,
+    ) -> Self {
+        Self {
             config: config.clone(),
             piece_values: config.get_piece_values(),
             stop,
+            pondering: Pondering::new(),
 
             hce,
             nnue,
 
             board: Board::default(),
-            game_history: AHashSet::new(),
-            nodes: 0,
+            game_history: GameHistory::new(),
+            nodes: Arc::new(AtomicU64::new(0)),
             killer_moves: [[None; 2]; MAX_DEPTH],
             current_pv: Vec::new(),
+            pv_table: PvTable::new(),
+            pv_lines: Vec::new(),
             max_depth_reached: 1,
+            search_moves: Vec::new(),
+            node_limit: None,
+            search_start: Instant::now(),
 
-            tt: TranspositionTable::new(1),
-            qs_tt: QSTable::new(1),
+            skill: Skill::from_config(config),
+
+            debug: false,
+            tt_probes: 0,
+            tt_hits: 0,
+
+            tt,
+            qs_tt,
+
+            thread_id,
+            helpers: Vec::new(),
+            helper_nodes: Vec::new(),
 
             search_stack: SearchStack::with_capacity(MAX_DEPTH),
 
             history_heuristic: HistoryHeuristic::new(1, 1, 1, 1, 1, 1),
             capture_history: CaptureHistory::new(1, 1, 1),
             continuation_history: Box::new(ContinuationHistory::new(1, 1, 1, 1)),
-        };
-
-        instance.configure(config, true);
-
-        instance
+            correction_history: CorrectionHistory::new(1, 1),
+        }
     }
 
     pub fn configure(&mut self, config: &EngineConfig, init: bool) {
@@ -114,12 +188,14 @@ impl Engine {
         // Update the HCE
         // TODO: Find a better way to do this
         self.piece_values = config.get_piece_values();
+        self.skill = Skill::from_config(config);
         self.hce = Box::new(hce::Evaluator::new(
             self.piece_values,
             config.get_hce_config(),
         ));
 
-        if init || old_config.hash_size.value != config.hash_size.value {
+        let resize_tables = init || old_config.hash_size.value != config.hash_size.value;
+        if resize_tables {
             self.configure_transposition_tables();
         }
 
@@ -134,6 +210,36 @@ impl Engine {
         if init || !self.continuation_history.matches_config(config) {
             self.continuation_history.configure(config);
         }
+
+        if init || !self.correction_history.matches_config(config) {
+            self.correction_history.configure(config);
+        }
+
+        if self.is_main_thread() {
+            self.configure_helpers(resize_tables);
+        }
+    }
+
+    /// Performs the action of a button option.
+    pub fn press_button(&mut self, name: &str) {
+        if self.config.clear_hash.option_name() == Some(name) {
+            self.init_game();
+        }
+    }
+
+    /// Replaces the neural network evaluation (e.g. after loading an `EvalFile`).
+    pub fn set_nnue(&mut self, nnue: Box<dyn NNUE>) {
+        self.nnue = Some(nnue);
+
+        // Cached static evals and learned corrections belong to the old network
+        self.tt.clear();
+        self.qs_tt.clear();
+        self.correction_history.reset();
+
+        // Helpers hold their own copy of the network
+        if self.is_main_thread() {
+            self.configure_helpers(true);
+        }
     }
 
     pub fn name(&self) -> String {
@@ -148,7 +254,7 @@ impl Engine {
         self.init_game();
     }
 
-    pub fn set_position(&mut self, board: Board, game_history: Option<AHashSet<u64>>) {
+    pub fn set_position(&mut self, board: Board, game_history: Option<GameHistory>) {
         self.board = board;
         self.game_history = game_history.unwrap_or_default();
     }
@@ -157,8 +263,34 @@ impl Engine {
         &self.board
     }
 
+    /// Number of positions known from the game before the root (since the last irreversible move).
+    pub fn game_history_len(&self) -> usize {
+        self.game_history.len()
+    }
+
+    /// Nodes searched by all threads in the last search.
+    pub fn nodes(&self) -> u64 {
+        self.total_nodes()
+    }
+
     pub fn stop(&mut self) {
         self.stop.store(true, Ordering::Relaxed);
+        self.pondering.end();
+    }
+
+    pub fn set_debug(&mut self, enabled: bool) {
+        self.debug = enabled;
+    }
+
+    /// Shared state for "go ponder": start it before starting a ponder search and
+    /// end it on ponderhit (to switch to normal time management) or stop.
+    pub fn pondering(&self) -> Pondering {
+        self.pondering.clone()
+    }
+
+    /// The expected reply to the best move, taken from the principal variation.
+    pub fn ponder_move(&self) -> Option<Move> {
+        self.current_pv.get(1).copied()
     }
 
     pub(super) fn init_game(&mut self) {
@@ -167,35 +299,103 @@ impl Engine {
         self.history_heuristic.reset();
         self.capture_history.reset();
         self.continuation_history.reset();
+        self.correction_history.reset();
         self.search_stack.clear();
+
+        for helper in &mut self.helpers {
+            helper.init_game();
+        }
+    }
+
+    #[inline]
+    pub(super) fn count_node(&self) {
+        // Only the owning thread writes, so a plain load/store is enough
+        let nodes = self.nodes.load(Ordering::Relaxed);
+        self.nodes.store(nodes + 1, Ordering::Relaxed);
+    }
+
+    /// Sends a diagnostic message if debug mode is on.
+    /// The message is only built when it will actually be sent.
+    pub(super) fn send_debug(
+        &self,
+        observer: Option<&dyn SearchObserver>,
+        message: impl FnOnce() -> String,
+    ) {
+        if !self.debug {
+            return;
+        }
+
+        if let Some(observer) = observer {
+            observer.on_message(&message());
+        }
+    }
+
+    /// Reports one info event per PV line, each at the depth it was completed at.
+    /// Extra lines searched for a weakened move are only used to pick it.
+    pub(super) fn send_search_info(&self, observer: &dyn SearchObserver, elapsed: Duration) {
+        let reported_lines = self.pv_lines.iter().take(self.config.multi_pv.value);
+        for (index, line) in reported_lines.enumerate() {
+            let info = self.line_info(line.depth, index, line.score, &line.pv, elapsed);
+            observer.on_info(&info);
+        }
     }
 
-    pub(super) fn send_search_info(
+    /// Reports the score of a failed aspiration search as a bound on the true score.
+    pub(super) fn send_bound_info(
         &self,
-        output: &Sender<UciOutput>,
-        current_depth: u8,
-        best_score: i16,
-        elapsed: std::time::Duration,
+        observer: &dyn SearchObserver,
+        depth: u8,
+        line_index: usize,
+        score: i16,
+        bound: ScoreBound,
     ) {
-        let found_checkmate = best_score.abs() >= evaluation::scores::MATE_VALUE - MAX_DEPTH as i16;
-        let nps = (self.nodes as f32 / elapsed.as_secs_f32()) as u32;
-
-        output
-            .send(UciOutput::Info(Info {
-                depth: current_depth,
-                sel_depth: self.max_depth_reached,
-                nodes: self.nodes,
-                nodes_per_second: nps,
-                hashfull: self.tt.hashfull(),
-                time: elapsed.as_millis() as u32,
-                score: if found_checkmate {
-                    convert_mate_score(best_score)
-                } else {
-                    convert_centipawn_score(best_score)
-                },
-                pv: pv_to_uci(&self.board, &self.current_pv),
-            }))
-            .unwrap();
+        if line_index >= self.config.multi_pv.value {
+            return;
+        }
+
+        let elapsed = self.search_start.elapsed();
+        let info = SearchInfo {
+            bound: Some(bound),
+            ..self.line_info(depth, line_index, score, &self.current_pv, elapsed)
+        };
+
+        observer.on_info(&info);
+    }
+
+    fn line_info(
+        &self,
+        depth: u8,
+        line_index: usize,
+        score: i16,
+        pv: &[Move],
+        elapsed: Duration,
+    ) -> SearchInfo {
+        let nodes = self.total_nodes();
+        let nps = (nodes as f64 / elapsed.as_secs_f64()) as u64;
+        let found_checkmate = score.abs() >= evaluation::scores::MATE_VALUE - MAX_DEPTH as i16;
+        let material = material_count(&self.board);
+
+        SearchInfo {
+            depth,
+            sel_depth: self.max_depth_reached,
+            multi_pv: line_index + 1,
+            nodes,
+            nodes_per_second: nps,
+            hashfull: self.tt.hashfull(),
+            time: elapsed,
+            score: if found_checkmate {
+                convert_mate_score(score)
+            } else {
+                convert_centipawn_score(score, material)
+            },
+            bound: None,
+            wdl: self
+                .config
+                .show_wdl
+                .value
+                .then(|| score_to_wdl(score, material)),
+            pv: pv.to_vec(),
+        }
     }
 
     fn configure_transposition_tables(&mut self) {
@@ -203,7 +403,7 @@ impl Engine {
         let qs_size_mb = total_size_mb / 3;
         let main_size_mb = total_size_mb - qs_size_mb;
 
-        self.tt = TranspositionTable::new(main_size_mb as usize);
-        self.qs_tt = QSTable::new(qs_size_mb as usize);
+        self.tt = Arc::new(TranspositionTable::new(main_size_mb as usize));
+        self.qs_tt = Arc::new(QSTable::new(qs_size_mb as usize));
     }
 }
@@ -0,0 +1,109 @@
+use std::cmp::Reverse;
+
+use cozy_chess::Move;
+
+use crate::{pruning::AspirationWindow, SearchObserver};
+
+use super::{skill::SKILL_MULTI_PV, Engine};
+
+/// A root move with its score and principal variation.
+#[derive(Debug, Clone)]
+pub(super) struct PvLine {
+    pub score: i16,
+    /// Depth the line was completed at
+    pub depth: u8,
+    pub pv: Vec<Move>,
+}
+
+impl Engine {
+    /// Searches the best `MultiPV` distinct lines at the given depth.
+    ///
+    /// Each line is a separate aspiration search at the root that skips the
+    /// root moves of the lines already found, so line N is the best move
+    /// not covered by lines 1..N.
+    ///
+    /// Returns the best move and score, or `None` if the search was stopped
+    /// before the first line was completed (the previous lines are kept).
+    /// Lines that were stopped later keep their result from the last iteration.
+    pub(super) fn search_pv_lines(
+        &mut self,
+        window: &mut AspirationWindow,
+        depth: u8,
+        observer: Option<&dyn SearchObserver>,
+        on_fail: &mut impl FnMut(),
+    ) -> Option<(Move, i16)> {
+        let root_moves = self.root_move_count();
+        let line_count = self.pv_line_count().min(root_moves).max(1);
+
+        let previous_lines = std::mem::take(&mut self.pv_lines);
+        let mut excluded_moves = Vec::with_capacity(line_count);
+
+        for index in 0..line_count {
+            let previous = previous_lines.get(index);
+
+            // Seed move ordering and the window from the same line of the last iteration
+            self.current_pv = previous.map(|line| line.pv.clone()).unwrap_or_default();
+            window.begin_depth(depth, previous.map_or(0, |line| line.score));
+
+            let Some((mv, score)) =
+                self.aspiration_search(window, depth, &excluded_moves, observer, on_fail)
+            else {
+                break;
+            };
+
+            excluded_moves.push(mv);
+            self.pv_lines.push(PvLine {
+                score,
+                depth,
+                pv: self.current_pv.clone(),
+            });
+        }
+
+        if self.pv_lines.is_empty() {
+            self.pv_lines = previous_lines;
+            self.current_pv = self
+                .pv_lines
+                .first()
+                .map(|line| line.pv.clone())
+                .unwrap_or_default();
+            return None;
+        }
+
+        // Later lines can still score above earlier ones due to search instability
+        self.pv_lines.sort_by_key(|line| Reverse(line.score));
+
+        // Only if stopped: fill the unfinished lines with the last iteration's
+        let missing = line_count - self.pv_lines.len();
+        let unfinished = previous_lines
+            .into_iter()
+            .filter(|line| !excluded_moves.contains(&line.pv[0]))
+            .take(missing);
+        self.pv_lines.extend(unfinished);
+
+        let best = &self.pv_lines[0];
+        self.current_pv = best.pv.clone();
+
+        Some((best.pv[0], best.score))
+    }
+
+    /// Lines to search: `MultiPV`, or more when a weakened move is picked among them.
+    fn pv_line_count(&self) -> usize {
+        match self.skill {
+            Some(_) => self.config.multi_pv.value.max(SKILL_MULTI_PV),
+            None => self.config.multi_pv.value,
+        }
+    }
+
+    fn root_move_count(&self) -> usize {
+        if !self.search_moves.is_empty() {
+            return self.search_moves.len();
+        }
+
+        let mut count = 0;
+        self.board.generate_moves(|moves| {
+            count += moves.len();
+            false
+        });
+        count
+    }
+}
@@ -1,12 +1,16 @@
-use cozy_chess::{Board, Move, Piece};
-use utils::{game_phase, is_capture};
+use std::sync::atomic::Ordering;
+
+use cozy_chess::{BitBoard, Board, Move, Piece};
+use utils::{game_phase, is_capture, make_move};
 
 use crate::{
+    move_ordering::MainMoveGenerator,
     pruning::{
-        can_futility_prune, can_null_move_prune, can_razor_prune, can_reverse_futility_prune,
-        futility_margin, null_move_reduction, razor_margin, rfp_margin, RAZOR_NEAR_MATE,
+        can_futility_prune, can_null_move_prune, can_probcut, can_razor_prune,
+        can_reverse_futility_prune, futility_margin, null_move_reduction, probcut_beta,
+        razor_margin, rfp_margin, tt_refutes_probcut, RAZOR_NEAR_MATE,
     },
-    stack::SearchNode,
+    transposition::ProbeResult,
     utils::see::see,
 };
 
@@ -65,7 +69,7 @@ impl Engine {
             return None;
         }
         // Q search with null window
-        let (value, _) = self.quiescence_search(board, alpha - 1, alpha, depth);
+        let value = self.quiescence_search(board, alpha - 1, alpha, depth);
         if value < alpha && value.abs() < RAZOR_NEAR_MATE {
             Some(value)
         } else {
@@ -185,8 +189,8 @@ impl Engine {
         );
 
         // Do a reduced depth null search to check if our position is still good enough
-        self.search_stack.push(SearchNode::new(nm_board.hash()));
-        let (score, _) = self.search_subtree(
+        self.search_stack.push_null(nm_board.hash());
+        let score = self.search_subtree(
             &nm_board,
             depth + 1,
             max_depth - r,
@@ -202,9 +206,9 @@ impl Engine {
             // Zugzwang check: at shallow depths, verify with a real search.
             // In zugzwang, passing is better than any legal move, so null move gives false positive.
             if base_remaining <= 6 {
-                self.search_stack.push(SearchNode::new(nm_board.hash()));
+                self.search_stack.push_null(nm_board.hash());
                 let verify_depth = max_depth - r.saturating_sub(1);
-                let (v_score, _) = self.search_subtree(
+                let v_score = self.search_subtree(
                     &nm_board,
                     depth + 1,
                     verify_depth,
@@ -238,6 +242,7 @@ impl Engine {
         in_check: bool,
         is_pv_node: bool,
         static_eval: i16,
+        raw_eval: i16,
         beta: i16,
         hash: u64,
         depth: u8,
@@ -268,7 +273,7 @@ impl Engine {
                 depth,
                 rfp_depth,
                 beta,
-                Some(static_eval),
+                Some(raw_eval),
                 alpha,
                 beta,
                 None,
@@ -278,6 +283,134 @@ impl Engine {
         None
     }
 
+    /// ProbCut: if a good capture beats beta by a margin in a reduced search,
+    /// a full-depth search would very likely fail high too. Each capture is first
+    /// verified with a cheap quiescence search before paying for the reduced search.
+    ///
+    /// <https://www.chessprogramming.org/ProbCut>
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn try_probcut(
+        &mut self,
+        board: &Board,
+        depth: u8,
+        max_depth: u8,
+        beta: i16,
+        hash: u64,
+        remaining_depth: u8,
+        in_check: bool,
+        is_pv_node: bool,
+        tt_entry: Option<ProbeResult>,
+        static_eval: i16,
+        raw_eval: i16,
+        threats: BitBoard,
+    ) -> Option<i16> {
+        if !can_probcut(
+            remaining_depth,
+            in_check,
+            is_pv_node,
+            beta,
+            self.config.probcut_min_depth.value,
+        ) {
+            return None;
+        }
+
+        let probcut_beta = probcut_beta(beta, self.config.probcut_margin.value);
+
+        // A recent enough search already failed to reach the raised beta
+        if tt_entry.is_some_and(|tt| {
+            tt_refutes_probcut(
+                tt.depth,
+                tt.value,
+                remaining_depth,
+                self.config.probcut_reduction.value,
+                probcut_beta,
+            )
+        }) {
+            return None;
+        }
+        let probcut_max_depth = max_depth
+            .saturating_sub(self.config.probcut_reduction.value)
+            .max(depth + 1);
+        let phase = game_phase(board);
+
+        let prev_to = self
+            .continuation_history
+            .get_prev_to_squares(self.search_stack.as_slice());
+        let mut movegen = MainMoveGenerator::new(
+            tt_entry
+                .and_then(|tt| tt.best_move)
+                .filter(|&m| is_capture(board, m)),
+            [None; 2],
+            &prev_to,
+            phase,
+            self.piece_values,
+            self.config.quiet_check_bonus.value,
+            threats,
+        );
+
+        while let Some(m) = movegen.next(
+            board,
+            &self.history_heuristic,
+            &self.capture_history,
+            &self.continuation_history,
+        ) {
+            // Only captures are tried, wherever the generator yields them
+            if !is_capture(board, m) {
+                continue;
+            }
+            // The capture must win enough material to make up the gap to the raised beta
+            if !see(
+                board,
+                m,
+                phase,
+                &self.piece_values,
+                probcut_beta - static_eval,
+            ) {
+                continue;
+            }
+
+            let moved_piece = board.piece_on(m.from).unwrap();
+            let new_board = make_move(board, m);
+
+            self.search_stack
+                .push_move(new_board.hash(), m, moved_piece, true);
+            let mut value =
+                -self.quiescence_search(&new_board, -probcut_beta, -probcut_beta + 1, depth + 1);
+            if value >= probcut_beta {
+                value = -self.search_subtree(
+                    &new_board,
+                    depth + 1,
+                    probcut_max_depth,
+                    -probcut_beta,
+                    -probcut_beta + 1,
+                    true,
+                    true,
+                );
+            }
+            self.search_stack.pop();
+
+            if self.stop.load(Ordering::Relaxed) {
+                return None;
+            }
+
+            if value >= probcut_beta {
+                self.tt.store(
+                    hash,
+                    depth,
+                    probcut_max_depth,
+                    value,
+                    Some(raw_eval),
+                    probcut_beta - 1,
+                    probcut_beta,
+                    Some(m),
+                );
+                return Some(value);
+            }
+        }
+
+        None
+    }
+
     /// Internal Iterative Deepening: do a shallow search to get a best move for ordering when TT misses.
     ///
     /// <https://www.chessprogramming.org/Internal_Iterative_Deepening>
@@ -299,7 +432,7 @@ impl Engine {
             return None;
         }
         let shallow_max = max_depth.saturating_sub(self.config.iid_reduction.value);
-        let (.., shallow_line) = self.search_subtree(
+        self.search_subtree(
             board,
             depth,
             shallow_max,
@@ -308,6 +441,6 @@ impl Engine {
             try_null_move,
             false, // disable nested IID
         );
-        shallow_line.first().copied()
+        self.pv_table.line(depth).first().copied()
     }
 }
@@ -0,0 +1,135 @@
+use cozy_chess::{Move, Square};
+
+use crate::{transposition::Bound, MAX_DEPTH};
+
+use super::Engine;
+
+const NO_MOVE: Move = Move {
+    from: Square::A1,
+    to: Square::A1,
+    promotion: None,
+};
+
+/// Triangular PV table: row `ply` holds the best line found from that ply.
+/// When a move becomes the best at a node, its row is rebuilt from the move
+/// and the row of the next ply, so no PV is allocated during the search.
+///
+/// <https://www.chessprogramming.org/Triangular_PV-Table>
+pub(super) struct PvTable {
+    moves: Box<[[Move; MAX_DEPTH]]>,
+    lengths: [usize; MAX_DEPTH],
+}
+
+impl PvTable {
+    pub fn new() -> Self {
+        Self {
+            moves: vec![[NO_MOVE; MAX_DEPTH]; MAX_DEPTH].into_boxed_slice(),
+            lengths: [0; MAX_DEPTH],
+        }
+    }
+
+    /// Forgets the line at this ply. Called when entering a node.
+    #[inline]
+    pub fn clear(&mut self, ply: u8) {
+        if let Some(length) = self.lengths.get_mut(ply as usize) {
+            *length = 0;
+        }
+    }
+
+    /// Sets the line at this ply to `mv` followed by the line of the next ply.
+    /// Plies past `MAX_DEPTH` (deep quiescence) are not tracked.
+    #[inline]
+    pub fn update(&mut self, ply: u8, mv: Move) {
+        let ply = ply as usize;
+        if ply >= MAX_DEPTH {
+            return;
+        }
+
+        let child_length = self
+            .lengths
+            .get(ply + 1)
+            .map_or(0, |&len| len.min(MAX_DEPTH - 1));
+        let (rows, child_rows) = self.moves.split_at_mut(ply + 1);
+        let row = &mut rows[ply];
+
+        row[0] = mv;
+        if child_length > 0 {
+            row[1..=child_length].copy_from_slice(&child_rows[0][..child_length]);
+        }
+        self.lengths[ply] = child_length + 1;
+    }
+
+    pub fn line(&self, ply: u8) -> &[Move] {
+        let ply = ply as usize;
+        if ply >= MAX_DEPTH {
+            return &[];
+        }
+        &self.moves[ply][..self.lengths[ply]]
+    }
+}
+
+impl Engine {
+    /// Continues the current PV with the best moves stored in the transposition table.
+    /// Nodes cut off by a TT hit end the PV early, but their entries still know the line.
+    pub(super) fn extend_pv_from_tt(&mut self) {
+        let mut board = self.board.clone();
+        let mut seen = vec![board.hash()];
+
+        for &mv in &self.current_pv {
+            if board.try_play(mv).is_err() {
+                return;
+            }
+            seen.push(board.hash());
+        }
+
+        while self.current_pv.len() < MAX_DEPTH {
+            let ply = self.current_pv.len() as u8;
+            let Some(entry) = self.tt.probe(board.hash(), ply) else {
+                break;
+            };
+            // A fail-low entry's move is not known to be best
+            if entry.bound == Bound::Upper {
+                break;
+            }
+            let Some(mv) = entry.best_move.filter(|&mv| board.is_legal(mv)) else {
+                break;
+            };
+
+            board.play_unchecked(mv);
+            // Stop at a repetition, which would loop forever
+            if seen.contains(&board.hash()) {
+                break;
+            }
+            seen.push(board.hash());
+            self.current_pv.push(mv);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cozy_chess::{util::parse_uci_move, Board};
+
+    use super::*;
+
+    #[test]
+    fn test_update_prepends_child_line() {
+        let board = Board::default();
+        let e4 = parse_uci_move(&board, "e2e4").unwrap();
+        let d4 = parse_uci_move(&board, "d2d4").unwrap();
+        let nf3 = parse_uci_move(&board, "g1f3").unwrap();
+
+        let mut table = PvTable::new();
+        table.clear(2);
+        table.update(2, nf3);
+        table.update(1, d4);
+        table.update(0, e4);
+        assert_eq!(table.line(0), &[e4, d4, nf3]);
+
+        // A leaf below ply 1 resets its line
+        table.clear(2);
+        table.update(1, nf3);
+        assert_eq!(table.line(1), &[nf3]);
+        assert!(table.line(MAX_DEPTH as u8).is_empty());
+    }
+}
@@ -1,6 +1,6 @@
 use std::sync::atomic::Ordering;
 
-use cozy_chess::{Board, Color, Move, Piece, Rank};
+use cozy_chess::{Board, Color, Piece, Rank};
 use evaluation::scores::{MATE_VALUE, SCORE_INF};
 use utils::flip_eval_perspective;
 use utils::{game_phase, has_check, make_move, Position};
@@ -26,23 +26,23 @@ impl Engine {
         mut alpha: i16,
         mut beta: i16,
         depth: u8,
-    ) -> (i16, Vec<Move>) {
+    ) -> i16 {
         // Check if we should stop searching
         if self.stop.load(Ordering::Relaxed) {
-            return (0, Vec::new());
+            return 0;
         }
 
-        self.nodes += 1;
+        self.count_node();
         self.max_depth_reached = self.max_depth_reached.max(depth);
+        self.pv_table.clear(depth);
 
-        // If this position has been seen before, treat it as a draw
-        if self.search_stack.is_repetition(&self.game_history) {
-            return (0, Vec::new());
+        if self.is_draw(board) {
+            return 0;
         }
 
         let hash = self.search_stack.current().hash;
         if mate_distance_prune(&mut alpha, &mut beta, depth) {
-            return (alpha, Vec::new());
+            return alpha;
         }
 
         let in_check = has_check(board);
@@ -54,9 +54,9 @@ impl Engine {
         // tactical horizon, so any hit is trustworthy for cutoffs
         if let Some(tt) = self.qs_tt.probe(hash, in_check) {
             match tt.bound {
-                Bound::Exact => return (tt.value, Vec::new()),
-                Bound::Lower if tt.value >= beta => return (tt.value, Vec::new()),
-                Bound::Upper if tt.value <= alpha => return (tt.value, Vec::new()),
+                Bound::Exact => return tt.value,
+                Bound::Lower if tt.value >= beta => return tt.value,
+                Bound::Upper if tt.value <= alpha => return tt.value,
                 _ => {}
             }
         }
@@ -72,7 +72,7 @@ impl Engine {
             if stand_pat >= beta {
                 self.qs_tt
                     .store(hash, stand_pat, original_alpha, original_beta, in_check);
-                return (stand_pat, Vec::new());
+                return stand_pat;
             }
 
             let total_material = self.piece_values.total_material(board, phase);
@@ -100,14 +100,13 @@ impl Engine {
                 if stand_pat + big_delta < alpha {
                     self.qs_tt
                         .store(hash, stand_pat, original_alpha, original_beta, in_check);
-                    return (stand_pat, Vec::new());
+                    return stand_pat;
                 }
             }
 
             alpha = alpha.max(stand_pat);
         }
 
-        let mut best_line = Vec::new();
         let mut best_eval = if in_check { -SCORE_INF } else { stand_pat };
 
         let mut moves = QMoveGenerator::new(
@@ -166,8 +165,7 @@ impl Engine {
             self.qs_tt.prefetch(child_hash);
 
             self.search_stack.push(SearchNode::new(child_hash));
-            let (child_score, mut child_line) =
-                self.quiescence_search(&new_board, -beta, -alpha, depth + 1);
+            let child_score = self.quiescence_search(&new_board, -beta, -alpha, depth + 1);
             self.search_stack.pop();
 
             let value = -child_score;
@@ -179,8 +177,7 @@ impl Engine {
 
             if value > best_eval {
                 best_eval = value;
-                child_line.insert(0, mv);
-                best_line = child_line;
+                self.pv_table.update(depth, mv);
                 alpha = alpha.max(best_eval);
             }
 
@@ -191,11 +188,11 @@ impl Engine {
 
         // If in check and no legal moves improved the position, it's checkmate
         if in_check && best_eval == -SCORE_INF {
-            return (-(MATE_VALUE - depth as i16), Vec::new());
+            return -(MATE_VALUE - depth as i16);
         }
 
         self.qs_tt
             .store(hash, best_eval, original_alpha, original_beta, in_check);
-        (best_eval, best_line)
+        best_eval
     }
 }
@@ -1,62 +1,115 @@
-use std::sync::{atomic::Ordering, mpsc::Sender, Arc};
+use std::sync::{atomic::Ordering, Arc};
+use std::thread;
+use std::time::{Duration, Instant};
 
 use arrayvec::ArrayVec;
 use cozy_chess::{BitBoard, Board, Move, Piece};
 use evaluation::scores::{MATE_VALUE, SCORE_INF};
-use uci::{
-    commands::{GoParams, Info, Score},
-    UciOutput,
-};
 use utils::{
-    flip_eval_perspective, game_phase, has_check, has_legal_moves, is_capture, make_move, Position,
+    collect_legal_moves, flip_eval_perspective, game_phase, has_check, has_legal_moves, is_capture,
+    is_fifty_move_draw, make_move, only_move, Position,
 };
 
 use crate::{
-    extensions,
+    extensions::{self, ExtensionContext, Singularity},
     move_ordering::{MainMoveGenerator, MAX_CAPTURES, MAX_QUIETS},
     pruning::{iir, lmr, mate_distance_prune, should_lmp_prune, AspirationWindow, Pass},
     stack::SearchNode,
     time_control::SearchController,
     transposition::Bound,
+    ScoreBound, SearchInfo, SearchLimits, SearchObserver, SearchScore,
 };
 
 use super::{Engine, MAX_DEPTH};
 
+/// Search time after which the root move being searched is reported.
+const CURRMOVE_DELAY: Duration = Duration::from_secs(1);
+
 impl Engine {
     /// Iterative deepening search with aspiration windows.
     ///
     /// Returns the best move and score, or `None` if already in checkmate.
     pub fn search(
         &mut self,
-        params: &GoParams,
-        output: Option<&Sender<UciOutput>>,
+        limits: &SearchLimits,
+        observer: Option<&dyn SearchObserver>,
     ) -> Option<(Move, i16)> {
+        self.init_search();
+
         // Check for checkmate (no legal moves when in check)
         if !has_legal_moves(&self.board) && has_check(&self.board) {
-            if let Some(output) = output {
-                output
-                    .send(UciOutput::Info(Info {
-                        score: Score::Mate(0),
-                        ..Default::default()
-                    }))
-                    .unwrap();
+            if let Some(observer) = observer {
+                observer.on_info(&SearchInfo {
+                    score: SearchScore::Mate(0),
+                    ..Default::default()
+                });
             }
             return None;
         }
 
-        self.init_search();
+        self.tt.age();
 
-        let mut window = AspirationWindow::new(
-            self.config.aspiration_window_size.value,
-            self.config.aspiration_window_widen.value,
-            self.config.aspiration_window_depth.value,
-        );
+        self.search_moves = limits
+            .search_moves
+            .iter()
+            .copied()
+            .filter(|&mv| self.board.is_legal(mv))
+            .collect();
+
+        // Helpers are moved out for the duration of the search so they can be
+        // borrowed by their threads while the main thread keeps `&mut self`
+        let mut helpers = std::mem::take(&mut self.helpers);
+        let has_helpers = !helpers.is_empty();
+        for helper in &mut helpers {
+            helper.set_position(self.board.clone(), Some(self.game_history.clone()));
+            helper.init_search();
+            helper.search_moves = self.search_moves.clone();
+        }
+
+        let result = thread::scope(|scope| {
+            for helper in &mut helpers {
+                scope.spawn(move || helper.helper_search());
+            }
+
+            let result = self.main_search(limits, observer);
+
+            // Main thread is done, so helpers should stop as well
+            if has_helpers {
+                self.stop.store(true, Ordering::Relaxed);
+            }
+
+            result
+        });
+
+        self.helpers = helpers;
+        result
+    }
+
+    /// Iterative deepening on the main thread, driving time control and progress reporting.
+    fn main_search(
+        &mut self,
+        limits: &SearchLimits,
+        observer: Option<&dyn SearchObserver>,
+    ) -> Option<(Move, i16)> {
+        let mut window = self.new_aspiration_window();
 
         let mut controller =
-            SearchController::new(params, &self.board, self.config.move_overhead.value as u64);
+            SearchController::new(limits, &self.board, self.config.move_overhead.value as u64);
         let stop = Arc::clone(&self.stop);
         controller.on_stop(move || stop.store(true, Ordering::Relaxed));
+        if limits.ponder {
+            controller.on_ponder(self.pondering.clone());
+        }
+        if let Some(skill) = self.skill {
+            controller.limit_search(skill.max_depth(), skill.max_nodes());
+        }
         controller.start_timer();
+        self.node_limit = controller.node_limit();
+
+        self.send_debug(observer, || format!("evaluation {}", self.eval_name()));
+        self.send_debug(observer, || {
+            format!("time budget {}", controller.describe_budget())
+        });
 
         let mut depth = 1;
         let mut best_move = None;
@@ -69,69 +122,158 @@ impl Engine {
                 break;
             }
 
-            window.begin_depth(depth, best_score);
-            let mut retries = 0;
+            let mut researches = 0;
+            let result = self.search_pv_lines(&mut window, depth, observer, &mut || {
+                researches += 1;
+                controller.on_aspiration_failure()
+            });
 
-            loop {
-                let (alpha, beta) = window.bounds();
-                let (mv, score) = self.search_root(depth, alpha, beta);
+            if researches > 0 {
+                self.send_debug(observer, || {
+                    format!("depth {} aspiration re-searches {}", depth, researches)
+                });
+            }
 
-                if mv.is_none() {
-                    break;
+            if let Some((mv, score)) = result {
+                best_move = Some(mv);
+                best_score = score;
+
+                controller.on_iteration_complete(depth, score, best_move);
+
+                if let Some(observer) = observer {
+                    self.send_search_info(observer, controller.elapsed());
                 }
+            }
 
-                match window.analyse_pass(score) {
-                    Pass::Hit(s) => {
-                        best_move = mv;
-                        best_score = s;
+            depth += 1;
+        }
 
-                        controller.on_iteration_complete(depth, s, mv);
+        self.send_debug(observer, || {
+            let hit_rate = self.tt_hits as f64 / self.tt_probes.max(1) as f64;
+            format!(
+                "tt hits {}/{} ({:.1}%)",
+                self.tt_hits,
+                self.tt_probes,
+                hit_rate * 100.0
+            )
+        });
+
+        // A node budget smaller than the first iteration still has to return a move
+        if best_move.is_none() {
+            best_move = collect_legal_moves(&self.board)
+                .into_iter()
+                .find(|mv| self.search_moves.is_empty() || self.search_moves.contains(mv));
+        }
 
-                        if let Some(out) = output {
-                            self.send_search_info(out, depth, s, controller.elapsed());
-                        }
-                        break;
+        // UCI forbids sending bestmove while pondering, even if the search is done
+        controller.wait_for_ponderhit();
+        // The search is over, so the timer must not stop whatever runs next
+        controller.stop_timer();
+
+        if let Some(skill) = self.skill {
+            let picked = skill
+                .pick_line(&self.pv_lines, &mut rand::thread_rng())
+                .cloned();
+            if let Some(line) = picked {
+                self.send_debug(observer, || {
+                    format!("skill picked line with score {}", line.score)
+                });
+
+                best_move = Some(line.pv[0]);
+                best_score = line.score;
+                // Keeps the ponder move consistent with the move played
+                self.current_pv = line.pv;
+            }
+        }
+
+        best_move.map(|mv| (mv, best_score))
+    }
+
+    pub(super) fn new_aspiration_window(&self) -> AspirationWindow {
+        AspirationWindow::new(
+            self.config.aspiration_window_size.value,
+            self.config.aspiration_window_widen.value,
+            self.config.aspiration_window_depth.value,
+        )
+    }
+
+    /// Searches the root at the given depth, widening the aspiration window until
+    /// the score falls inside it. Calls `on_fail` for every failed attempt.
+    ///
+    /// Returns `None` if the search was stopped.
+    pub(super) fn aspiration_search(
+        &mut self,
+        window: &mut AspirationWindow,
+        depth: u8,
+        excluded_moves: &[Move],
+        observer: Option<&dyn SearchObserver>,
+        on_fail: &mut impl FnMut(),
+    ) -> Option<(Move, i16)> {
+        let mut retries = 0;
+
+        loop {
+            let (alpha, beta) = window.bounds();
+            let (mv, score) = self.search_root(depth, alpha, beta, excluded_moves, observer);
+            let mv = mv?;
+
+            match window.analyse_pass(score) {
+                Pass::Hit(s) => return Some((mv, s)),
+                pass => {
+                    on_fail();
+
+                    if let Some(observer) = observer {
+                        let bound = match pass {
+                            Pass::FailHigh => ScoreBound::Lower,
+                            _ => ScoreBound::Upper,
+                        };
+                        self.send_bound_info(observer, depth, excluded_moves.len(), score, bound);
                     }
-                    _ => {
-                        controller.on_aspiration_failure();
 
-                        retries += 1;
+                    retries += 1;
 
-                        if retries >= self.config.aspiration_window_retries.value {
-                            window.fully_extend();
-                            retries = 0;
-                        }
+                    if retries >= self.config.aspiration_window_retries.value {
+                        window.fully_extend();
+                        retries = 0;
                     }
                 }
             }
-
-            depth += 1;
         }
-
-        best_move.map(|mv| (mv, best_score))
     }
 
     /// Initializes the search - resets all state for a new search.
-    fn init_search(&mut self) {
-        self.stop.store(false, Ordering::Relaxed);
-
-        self.nodes = 0;
+    ///
+    /// The stop flag is left alone: it is cleared by whoever starts the search
+    /// (e.g. on "go"), so a stop that arrives before the search begins is not lost.
+    pub(super) fn init_search(&mut self) {
+        self.nodes.store(0, Ordering::Relaxed);
         self.max_depth_reached = 1;
+        self.node_limit = None;
+        self.search_start = Instant::now();
+        self.tt_probes = 0;
+        self.tt_hits = 0;
         self.current_pv.clear();
+        self.pv_lines.clear();
 
         self.search_stack.clear();
         self.search_stack.push(SearchNode::new(self.board.hash()));
+    }
 
-        self.tt.age();
+    /// Draw by repetition or the fifty-move rule.
+    pub(super) fn is_draw(&self, board: &Board) -> bool {
+        self.search_stack
+            .is_repetition(&self.game_history, board.halfmove_clock())
+            || is_fifty_move_draw(board)
     }
 
-    /// Root search with the given alpha-beta window.
+    /// Root search with the given alpha-beta window, skipping `excluded_moves`.
     /// Called once per aspiration window attempt at each depth.
     pub(super) fn search_root(
         &mut self,
         depth: u8,
         mut alpha: i16,
         beta: i16,
+        excluded_moves: &[Move],
+        observer: Option<&dyn SearchObserver>,
     ) -> (Option<Move>, i16) {
         let best_move = self.current_pv.first().cloned();
 
@@ -153,6 +295,7 @@ impl Engine {
 
         let mut best_score = -SCORE_INF;
         let mut current_best_move = None;
+        let mut move_number = 0;
 
         // Negamax at root: call search_subtree with flipped window, then negate result
         while let Some(m) = moves.next(
@@ -161,13 +304,34 @@ impl Engine {
             &self.capture_history,
             &self.continuation_history,
         ) {
+            // Already reported as a better MultiPV line
+            if excluded_moves.contains(&m) {
+                continue;
+            }
+
+            // Restricted by "go searchmoves"
+            if !self.search_moves.is_empty() && !self.search_moves.contains(&m) {
+                continue;
+            }
+
+            move_number += 1;
+            if let Some(observer) = observer {
+                // Only worth reporting once iterations take a noticeable amount of time
+                if self.search_start.elapsed() >= CURRMOVE_DELAY {
+                    observer.on_current_move(depth, m, move_number);
+                }
+            }
+
             let moved_piece = self.board.piece_on(m.from).unwrap();
             let new_board = make_move(&self.board, m);
 
-            self.search_stack
-                .push_move(new_board.hash(), m, moved_piece);
-            let (child_value, mut pv) =
-                self.search_subtree(&new_board, 1, depth, -beta, -alpha, true, true);
+            self.search_stack.push_move(
+                new_board.hash(),
+                m,
+                moved_piece,
+                is_capture(&self.board, m),
+            );
+            let child_value = self.search_subtree(&new_board, 1, depth, -beta, -alpha, true, true);
             let score = -child_value;
             self.search_stack.pop();
 
@@ -176,24 +340,25 @@ impl Engine {
                 return (None, 0);
             }
 
-            pv.insert(0, m);
-
             if score > best_score {
                 best_score = score;
                 current_best_move = Some(m);
-                self.current_pv = pv;
+                self.pv_table.update(0, m);
+                self.current_pv = self.pv_table.line(0).to_vec();
             }
 
             alpha = alpha.max(best_score);
         }
 
+        self.extend_pv_from_tt();
+
         (current_best_move, best_score)
     }
 
     /// Recursive alpha-beta search with PVS.
     ///
     /// Applies pruning, reductions, and searches child nodes.
-    /// Returns (score, pv).
+    /// Returns the score; the principal variation is left in the PV table.
     #[allow(clippy::too_many_arguments)]
     pub(super) fn search_subtree(
         &mut self,
@@ -204,20 +369,25 @@ impl Engine {
         mut beta: i16,
         try_null_move: bool,
         allow_iid: bool,
-    ) -> (i16, Vec<Move>) {
+    ) -> i16 {
         if self.stop.load(Ordering::Relaxed) {
-            return (0, Vec::new());
+            return 0;
+        }
+        self.count_node();
+        self.pv_table.clear(depth);
+
+        if self.node_limit_reached() {
+            self.stop.store(true, Ordering::Relaxed);
+            return 0;
         }
-        self.nodes += 1;
 
-        // If this position has been seen before, treat it as a draw
-        if self.search_stack.is_repetition(&self.game_history) {
-            return (0, Vec::new());
+        if self.is_draw(board) {
+            return 0;
         }
 
         let hash = self.search_stack.current().hash;
         if mate_distance_prune(&mut alpha, &mut beta, depth) {
-            return (alpha, Vec::new());
+            return alpha;
         }
 
         if depth >= max_depth {
@@ -228,30 +398,36 @@ impl Engine {
         let original_alpha = alpha;
         let mut maybe_tt_move = None;
         let mut tt_static_eval = None;
+        let mut tt_entry = None;
+
+        // Set while verifying whether the TT move is singular. The TT entry
+        // belongs to the full node, so it must neither cut off nor be overwritten.
+        let excluded_move = self.search_stack.excluded_move();
 
+        self.tt_probes += 1;
         if let Some(tt) = self.tt.probe(hash, depth) {
+            self.tt_hits += 1;
+
             // Only trust value/bound for cutoffs if the TT entry comes from a
             // search at least as deep as we need. Shallow results may have
             // missed tactics and can't safely prune the current search
             let needed_depth = max_depth - depth;
-            if tt.depth >= needed_depth {
+            if tt.depth >= needed_depth && excluded_move.is_none() {
                 match tt.bound {
                     // Exact: previous search found true minimax value
-                    Bound::Exact => {
-                        return (tt.value, tt.best_move.map_or(Vec::new(), |m| vec![m]))
-                    }
+                    Bound::Exact => return tt.value,
                     // Lower: previous search failed high (value >= beta), so value is at least this good
                     Bound::Lower => {
                         alpha = alpha.max(tt.value);
                         if alpha >= beta {
-                            return (tt.value, tt.best_move.map_or(Vec::new(), |m| vec![m]));
+                            return tt.value;
                         }
                     }
                     // Upper: previous search failed low (value <= alpha), so value is at most this bad
                     Bound::Upper => {
                         beta = beta.min(tt.value);
                         if beta <= alpha {
-                            return (beta, tt.best_move.map_or(Vec::new(), |m| vec![m]));
+                            return beta;
                         }
                     }
                 }
@@ -261,6 +437,7 @@ impl Engine {
             // even from shallow searches - these are still valuable hints!
             maybe_tt_move = tt.best_move;
             tt_static_eval = tt.static_eval;
+            tt_entry = Some(tt);
         }
 
         // PV-ness depends on the current window, so compute it after any TT bound tightening.
@@ -269,6 +446,7 @@ impl Engine {
         let position = Position::new(board);
         let phase = game_phase(board);
         let in_check = has_check(board);
+        let only_move = in_check && only_move(board);
         let remaining_depth = max_depth - depth;
 
         // Internal Iterative Reductions
@@ -280,12 +458,14 @@ impl Engine {
             self.config.iir_reduction.value,
         );
 
-        let static_eval = if let Some(tt_se) = tt_static_eval {
+        let raw_eval = if let Some(tt_se) = tt_static_eval {
             tt_se // Cached in TT
         } else {
-            let eval = self.eval(&position, phase);
+            let eval = self.raw_eval(&position, phase);
             flip_eval_perspective(board.side_to_move(), eval)
         };
+        // Corrections change during the search, so they are applied after the TT lookup
+        let static_eval = self.corrected_eval(board, raw_eval);
 
         self.search_stack
             .current_mut(|node| node.static_eval = Some(static_eval));
@@ -293,7 +473,7 @@ impl Engine {
         if let Some(score) =
             self.try_razor_prune(board, remaining_depth, alpha, depth, in_check, static_eval)
         {
-            return (score, Vec::new());
+            return score;
         }
 
         if let Some(score) = self.try_null_move_prune(
@@ -305,10 +485,10 @@ impl Engine {
             hash,
             remaining_depth,
             in_check,
-            try_null_move,
+            try_null_move && excluded_move.is_none(),
             Some(static_eval),
         ) {
-            return (score, Vec::new());
+            return score;
         }
 
         // Internal Iterative Deepening (IID)
@@ -334,6 +514,7 @@ impl Engine {
             in_check,
             is_pv_node,
             static_eval,
+            raw_eval,
             beta,
             hash,
             depth,
@@ -341,19 +522,40 @@ impl Engine {
             alpha,
             is_improving,
         ) {
-            return (score, Vec::new());
+            return score;
+        }
+
+        let threats = position.threats_for(board.side_to_move());
+
+        if excluded_move.is_none() {
+            if let Some(score) = self.try_probcut(
+                board,
+                depth,
+                max_depth,
+                beta,
+                hash,
+                remaining_depth,
+                in_check,
+                is_pv_node,
+                tt_entry,
+                static_eval,
+                raw_eval,
+                threats,
+            ) {
+                return score;
+            }
         }
 
         self.max_depth_reached = self.max_depth_reached.max(depth);
 
+        // Razoring, IID and ProbCut may have searched this node already
+        self.pv_table.clear(depth);
+
         let mut best_value = -SCORE_INF;
         let mut best_move = None;
-        let mut best_line = Vec::new();
 
         let mut best_move_depth = depth;
 
-        let threats = position.threats_for(board.side_to_move());
-
         let prev_to = self
             .continuation_history
             .get_prev_to_squares(self.search_stack.as_slice());
@@ -379,6 +581,10 @@ impl Engine {
             &self.capture_history,
             &self.continuation_history,
         ) {
+            if Some(m) == excluded_move {
+                continue;
+            }
+
             move_index += 1;
 
             // Late Move Pruning (LMP)
@@ -398,7 +604,28 @@ impl Engine {
                 continue;
             }
 
-            if let Some((value, mut line, is_quiet, searched_depth)) = self.search_move(
+            let mut singular_extension = 0;
+            if let Some(tt) =
+                tt_entry.filter(|tt| excluded_move.is_none() && tt.best_move == Some(m))
+            {
+                match self.try_singular_extension(
+                    board,
+                    depth,
+                    beta,
+                    remaining_depth,
+                    m,
+                    tt.value,
+                    tt.bound,
+                    tt.depth,
+                ) {
+                    Some(Singularity::Singular) => singular_extension = 1,
+                    Some(Singularity::MultiCut(value)) => return value,
+                    Some(Singularity::NotSingular) => singular_extension = -1,
+                    None => {}
+                }
+            }
+
+            if let Some((value, is_quiet, searched_depth)) = self.search_move(
                 board,
                 depth,
                 max_depth,
@@ -411,6 +638,8 @@ impl Engine {
                 is_improving,
                 static_eval,
                 threats,
+                only_move,
+                singular_extension,
             ) {
                 if self.stop.load(Ordering::Relaxed) {
                     break;
@@ -419,8 +648,7 @@ impl Engine {
                 if value > best_value {
                     best_value = value;
                     best_move = Some(m);
-                    line.insert(0, m);
-                    best_line = line;
+                    self.pv_table.update(depth, m);
                     best_move_depth = searched_depth;
                 }
 
@@ -451,32 +679,55 @@ impl Engine {
             }
         }
 
+        // Only the excluded move is legal, so it is singular
+        if move_index == -1 && excluded_move.is_some() {
+            return alpha;
+        }
+
         // Check for terminal position (no legal moves)
         if move_index == -1 {
             // No moves were found - either checkmate or stalemate
             return if in_check {
-                (-(MATE_VALUE - depth as i16), Vec::new()) // Checkmate
+                -(MATE_VALUE - depth as i16) // Checkmate
             } else {
-                (0, Vec::new()) // Stalemate
+                0 // Stalemate
             };
         }
 
+        if excluded_move.is_some() {
+            return best_value;
+        }
+
+        // Learn how far off the static eval was. Captures and promotions explain
+        // the gap on their own, and a bound on the wrong side of the eval says nothing.
+        let quiet_best = best_move.is_none_or(|m| !is_capture(board, m) && m.promotion.is_none());
+        let failed_high = best_value >= beta;
+        let failed_low = best_value <= original_alpha;
+        if !in_check
+            && quiet_best
+            && !(failed_high && best_value <= static_eval)
+            && !(failed_low && best_value >= static_eval)
+        {
+            self.correction_history
+                .update(board, remaining_depth, best_value, static_eval);
+        }
+
         // Store TT entry with the depth actually searched for the best move
         self.tt.store(
             hash,
             depth,
             best_move_depth,
             best_value,
-            Some(static_eval),
+            Some(raw_eval),
             original_alpha,
             beta,
             best_move,
         );
-        (best_value, best_line)
+        best_value
     }
 
     /// Searches a single move with per-move pruning and LMR.
-    /// Returns `None` if the move was pruned, otherwise (score, pv, is_quiet, depth).
+    /// Returns `None` if the move was pruned, otherwise (score, is_quiet, depth).
     #[allow(clippy::too_many_arguments)]
     pub(super) fn search_move(
         &mut self,
@@ -492,7 +743,9 @@ impl Engine {
         is_improving: bool,
         static_eval: i16,
         pre_move_threats: BitBoard,
-    ) -> Option<(i16, Vec<Move>, bool, u8)> {
+        only_move: bool,
+        singular_extension: i8,
+    ) -> Option<(i16, bool, u8)> {
         let moved_piece = board.piece_on(m.from).unwrap();
         let new_board = make_move(board, m);
         let child_hash = new_board.hash();
@@ -564,15 +817,43 @@ impl Engine {
             return None;
         }
 
-        let extension = extensions::get(board, &m, moved_piece, is_cap);
+        let node = self.search_stack.current();
+        let extension_config = self.config.get_extension_config();
+        let extension = extensions::get(
+            &ExtensionContext {
+                board,
+                m,
+                moved_piece,
+                is_capture: is_cap,
+                gives_check,
+                in_check,
+                only_move,
+                node: &node,
+            },
+            &extension_config,
+        );
+        // Only positive extensions draw from the path budget
+        let extension = extensions::within_budget(
+            extension + singular_extension.max(0) as u8,
+            node.extensions,
+            extension_config.budget,
+        );
+        let child = SearchNode {
+            is_capture: is_cap,
+            extensions: node.extensions + extension,
+            ..SearchNode::with_move(child_hash, m, moved_piece)
+        };
 
-        let extended_max_depth = max_depth + extension;
+        let extended_max_depth = max_depth
+            .saturating_add_signed(extension as i8 + singular_extension.min(0))
+            .min(MAX_DEPTH as u8)
+            .max(depth + 1);
         let reduced_max_depth = extended_max_depth.saturating_sub(reduction).max(depth + 1);
         let mut searched_depth = reduced_max_depth;
 
         // Initial search (reduced if LMR, null window if not first move)
-        self.search_stack.push_move(child_hash, m, moved_piece);
-        let (child_value, pv_line) = self.search_subtree(
+        self.search_stack.push(child);
+        let child_value = self.search_subtree(
             &new_board,
             depth + 1,
             reduced_max_depth,
@@ -583,13 +864,11 @@ impl Engine {
         );
         self.search_stack.pop();
         let mut value = -child_value;
-        let mut line = pv_line;
 
         // Re-search at full depth (if LMR was used and value > alpha)
         if reduction > 0 && value > alpha {
-            self.search_stack
-                .push(SearchNode::with_move(child_hash, m, moved_piece));
-            let (re_child_value, re_line) = self.search_subtree(
+            self.search_stack.push(child);
+            let re_child_value = self.search_subtree(
                 &new_board,
                 depth + 1,
                 extended_max_depth,
@@ -600,15 +879,13 @@ impl Engine {
             );
             self.search_stack.pop();
             value = -re_child_value;
-            line = re_line;
             searched_depth = extended_max_depth;
         }
 
         // Re-search with full window (if null window failed high in a PV node)
         if value > alpha && value < beta && !is_pv_move && is_pv_node {
-            self.search_stack
-                .push(SearchNode::with_move(child_hash, m, moved_piece));
-            let (full_child_value, full_line) = self.search_subtree(
+            self.search_stack.push(child);
+            let full_child_value = self.search_subtree(
                 &new_board,
                 depth + 1,
                 extended_max_depth,
@@ -619,12 +896,11 @@ impl Engine {
             );
             self.search_stack.pop();
             value = -full_child_value;
-            line = full_line;
             searched_depth = extended_max_depth;
         }
 
         let is_quiet = !is_cap && !is_promotion;
-        Some((value, line, is_quiet, searched_depth))
+        Some((value, is_quiet, searched_depth))
     }
 
     /// Handler called if a search fails high - updates history tables, killers, etc.
@@ -690,3 +966,261 @@ impl Engine {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::sync::atomic::{AtomicBool, AtomicU64};
+
+    use super::*;
+    use crate::EngineConfig;
+
+    /// Records the deepest completed iteration.
+    #[derive(Default)]
+    struct DepthRecorder {
+        depth: Cell<u8>,
+    }
+
+    impl SearchObserver for DepthRecorder {
+        fn on_info(&self, info: &SearchInfo) {
+            if info.bound.is_none() {
+                self.depth.set(self.depth.get().max(info.depth));
+            }
+        }
+    }
+
+    fn hce_engine() -> Engine {
+        let mut config = EngineConfig::default();
+        config.hash_size.value = 1;
+        let hce = Box::new(hce::Evaluator::new(
+            config.get_piece_values(),
+            config.get_hce_config(),
+        ));
+        Engine::new(&config, hce, None, Arc::new(AtomicBool::new(false)))
+    }
+
+    fn search_position(engine: &mut Engine, fen: &str, depth: u8) -> (Move, i16) {
+        engine.new_game();
+        engine.set_position(fen.parse().unwrap(), None);
+        let limits = SearchLimits {
+            depth: Some(depth),
+            ..Default::default()
+        };
+        engine.search(&limits, None).unwrap()
+    }
+
+    #[test]
+    fn test_fifty_move_rule() {
+        let mut engine = hce_engine();
+
+        // A queen up, but every move completes 50 moves without a capture or pawn move
+        let (_, score) = search_position(&mut engine, "8/8/8/4k3/8/8/8/KQ6 w - - 99 80", 4);
+        assert_eq!(score, 0);
+
+        // Checkmate on the last move still counts
+        let (mv, score) = search_position(&mut engine, "7k/8/6K1/8/8/8/8/R7 w - - 99 80", 4);
+        assert_eq!(mv.to_string(), "a1a8");
+        assert_eq!(score, MATE_VALUE - 1);
+    }
+
+    #[test]
+    fn test_node_limit_applies_from_first_iteration() {
+        let mut engine = hce_engine();
+        engine.new_game();
+        let limits = SearchLimits {
+            nodes: Some(10),
+            ..Default::default()
+        };
+        let (mv, _) = engine.search(&limits, None).unwrap();
+
+        assert!(engine.board.is_legal(mv));
+        assert!(engine.total_nodes() <= 11, "{} nodes", engine.total_nodes());
+    }
+
+    #[test]
+    fn test_node_limit_counts_helpers_periodically() {
+        let mut engine = hce_engine();
+        engine.node_limit = Some(2000);
+        engine.helper_nodes = vec![Arc::new(AtomicU64::new(1500))];
+
+        engine.nodes.store(1000, Ordering::Relaxed);
+        assert!(!engine.node_limit_reached());
+
+        engine.nodes.store(1024, Ordering::Relaxed);
+        assert!(engine.node_limit_reached());
+
+        // The main thread's own nodes are always checked
+        engine.helper_nodes.clear();
+        engine.nodes.store(2001, Ordering::Relaxed);
+        assert!(engine.node_limit_reached());
+    }
+
+    #[test]
+    fn test_skill_reports_only_multipv_lines() {
+        /// Records the highest line index reported.
+        #[derive(Default)]
+        struct LineRecorder {
+            max_line: Cell<usize>,
+        }
+
+        impl SearchObserver for LineRecorder {
+            fn on_info(&self, info: &SearchInfo) {
+                self.max_line.set(self.max_line.get().max(info.multi_pv));
+            }
+        }
+
+        let mut engine = hce_engine();
+        let mut config = engine.config.clone();
+        config.skill_level.value = 5;
+        engine.configure(&config, false);
+
+        let recorder = LineRecorder::default();
+        let limits = SearchLimits {
+            depth: Some(4),
+            ..Default::default()
+        };
+        engine.search(&limits, Some(&recorder)).unwrap();
+
+        assert_eq!(recorder.max_line.get(), 1);
+        assert!(engine.pv_lines.len() > 1);
+    }
+
+    #[test]
+    fn test_stopped_multipv_keeps_completed_lines() {
+        let mut engine = hce_engine();
+        let mut config = engine.config.clone();
+        config.multi_pv.value = 3;
+        engine.configure(&config, false);
+
+        let mut stopped_between_lines = false;
+        for nodes in (1..=10).map(|n| n * 2000) {
+            engine.new_game();
+            engine.stop.store(false, Ordering::Relaxed);
+            let limits = SearchLimits {
+                nodes: Some(nodes),
+                ..Default::default()
+            };
+            engine.search(&limits, None).unwrap();
+
+            // Lines completed at the last depth come first, the rest are from the one before
+            let lines = &engine.pv_lines;
+            assert_eq!(lines.len(), 3, "{} nodes", nodes);
+            assert!(lines[0].depth >= lines[1].depth && lines[1].depth >= lines[2].depth);
+            assert!(lines[0].depth - lines[2].depth <= 1);
+            for (index, line) in lines.iter().enumerate() {
+                assert!(lines[..index].iter().all(|other| other.pv[0] != line.pv[0]));
+            }
+
+            stopped_between_lines |= lines[0].depth > lines[2].depth;
+        }
+
+        assert!(stopped_between_lines);
+    }
+
+    #[test]
+    fn test_back_to_back_searches() {
+        let mut engine = hce_engine();
+
+        for _ in 0..5 {
+            // Ends at depth 1, usually before its 1 ms hard limit
+            let short = SearchLimits {
+                depth: Some(1),
+                move_time: Some(1),
+                ..Default::default()
+            };
+            engine.search(&short, None);
+            // The frontend clears the flag on "go"
+            engine.stop.store(false, Ordering::Relaxed);
+
+            // Takes far longer than 1 ms, so a leftover timer of the previous search
+            // would stop it before the last iteration
+            engine.new_game();
+            let deep = SearchLimits {
+                depth: Some(7),
+                ..Default::default()
+            };
+            let recorder = DepthRecorder::default();
+            engine.search(&deep, Some(&recorder));
+
+            assert_eq!(recorder.depth.get(), 7);
+        }
+    }
+
+    #[test]
+    fn test_stop_before_ponder_search_starts() {
+        let mut engine = hce_engine();
+        engine.new_game();
+
+        // "go ponder" followed by "stop" before the worker got to the search
+        engine.pondering().start();
+        engine.stop();
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        thread::spawn(move || {
+            let limits = SearchLimits {
+                ponder: true,
+                ..Default::default()
+            };
+            let _ = tx.send(engine.search(&limits, None));
+        });
+
+        let result = rx.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert!(result.is_some());
+    }
+
+    #[test]
+    fn test_pv_is_legal_and_reaches_depth() {
+        let mut engine = hce_engine();
+        let fen = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+        let (mv, _) = search_position(&mut engine, fen, 6);
+
+        let pv = engine.current_pv.clone();
+        assert_eq!(pv[0], mv);
+        assert!(pv.len() >= 6, "pv has {} moves", pv.len());
+
+        let mut board: Board = fen.parse().unwrap();
+        for mv in pv {
+            assert!(board.is_legal(mv));
+            board.play_unchecked(mv);
+        }
+    }
+
+    #[test]
+    fn test_probcut_stores_winning_capture() {
+        let mut engine = hce_engine();
+        engine.new_game();
+        engine.set_position("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1".parse().unwrap(), None);
+        engine.init_search();
+
+        let board = engine.board.clone();
+        let hash = board.hash();
+        let position = Position::new(&board);
+        let phase = game_phase(&board);
+        let raw_eval =
+            flip_eval_perspective(board.side_to_move(), engine.raw_eval(&position, phase));
+        let static_eval = engine.corrected_eval(&board, raw_eval);
+        let threats = position.threats_for(board.side_to_move());
+        let remaining_depth = engine.config.probcut_min_depth.value;
+
+        // Down a queen for a rook, but taking the queen clears the raised beta
+        let value = engine.try_probcut(
+            &board,
+            1,
+            1 + remaining_depth,
+            0,
+            hash,
+            remaining_depth,
+            false,
+            false,
+            None,
+            static_eval,
+            raw_eval,
+            threats,
+        );
+        assert!(value.is_some_and(|v| v >= engine.config.probcut_margin.value));
+
+        let tt = engine.tt.probe(hash, 1).unwrap();
+        assert!(tt.bound == Bound::Lower);
+        assert_eq!(tt.best_move, Some("d2d5".parse().unwrap()));
+    }
+}
@@ -0,0 +1,129 @@
+use rand::Rng;
+
+use crate::EngineConfig;
+
+use super::multipv::PvLine;
+
+/// Skill level at which the engine plays at full strength.
+///
+/// Levels are not calibrated to ratings, so `UCI_LimitStrength`/`UCI_Elo` are not
+/// offered until the levels have been measured against rated opponents.
+pub const MAX_SKILL_LEVEL: i32 = 20;
+
+/// Lines searched to choose a weaker move from, regardless of `MultiPV`.
+pub const SKILL_MULTI_PV: usize = 4;
+
+/// Nodes searched at skill level 0. Doubles every `NODES_DOUBLING_LEVELS` levels.
+const MIN_SKILL_NODES: f64 = 400.0;
+const NODES_DOUBLING_LEVELS: f64 = 1.75;
+
+/// The most a suboptimal move may trail the best one by to be considered (one pawn).
+const MAX_SKILL_DELTA: i16 = 100;
+
+/// Playing strength below the maximum (`Skill Level`).
+///
+/// Weakens play in three ways, each growing with lower levels:
+/// the search is capped in depth and nodes, and the move is picked at random
+/// among the near-best root moves, favouring better ones.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct Skill {
+    /// Fractional skill level in `0.0..MAX_SKILL_LEVEL`
+    level: f64,
+}
+
+impl Skill {
+    /// Returns the configured skill, or `None` when playing at full strength.
+    pub fn from_config(config: &EngineConfig) -> Option<Self> {
+        let level = config.skill_level.value as f64;
+
+        (level < MAX_SKILL_LEVEL as f64).then_some(Self { level })
+    }
+
+    pub fn max_depth(&self) -> u8 {
+        1 + self.level as u8
+    }
+
+    pub fn max_nodes(&self) -> u64 {
+        (MIN_SKILL_NODES * 2f64.powf(self.level / NODES_DOUBLING_LEVELS)) as u64
+    }
+
+    /// Picks one of the searched lines, sorted best first.
+    ///
+    /// Each line gets a random bonus, scaled by how weak the level is, on top of
+    /// its score. Lines far behind the best one rarely win, as the bonus is
+    /// limited by the spread of the scores (at most a pawn).
+    pub fn pick_line<'a>(&self, lines: &'a [PvLine], rng: &mut impl Rng) -> Option<&'a PvLine> {
+        let best_score = lines.first()?.score as i32;
+        let worst_score = lines.last()?.score as i32;
+        let delta = (best_score - worst_score).min(MAX_SKILL_DELTA as i32);
+        let weakness = 120 - 2 * self.level as i32;
+
+        lines.iter().max_by_key(|line| {
+            let score = line.score as i32;
+            let push = (weakness * (best_score - score) + delta * rng.gen_range(0..weakness)) / 128;
+            score + push
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    fn line(score: i16) -> PvLine {
+        PvLine {
+            score,
+            depth: 1,
+            pv: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_full_strength_by_default() {
+        assert_eq!(Skill::from_config(&EngineConfig::default()), None);
+    }
+
+    #[test]
+    fn test_skill_level_below_maximum() {
+        let mut config = EngineConfig::default();
+        config.skill_level.value = 5;
+
+        assert_eq!(Skill::from_config(&config), Some(Skill { level: 5.0 }));
+    }
+
+    #[test]
+    fn test_limits_grow_with_level() {
+        let weak = Skill { level: 0.0 };
+        let strong = Skill { level: 19.0 };
+
+        assert!(weak.max_depth() < strong.max_depth());
+        assert!(weak.max_nodes() < strong.max_nodes());
+    }
+
+    #[test]
+    fn test_pick_line_varies_between_close_moves() {
+        let lines = [line(50), line(40), line(30)];
+        let skill = Skill { level: 0.0 };
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let picked: Vec<i16> = (0..100)
+            .map(|_| skill.pick_line(&lines, &mut rng).unwrap().score)
+            .collect();
+
+        assert!(picked.iter().any(|&score| score != 50));
+    }
+
+    #[test]
+    fn test_pick_line_avoids_blunders_at_high_levels() {
+        let lines = [line(50), line(40), line(-800)];
+        let skill = Skill { level: 19.0 };
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for _ in 0..100 {
+            let picked = skill.pick_line(&lines, &mut rng).unwrap();
+            assert_ne!(picked.score, -800);
+        }
+    }
+}
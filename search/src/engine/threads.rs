@@ -0,0 +1,105 @@
+use std::sync::{atomic::Ordering, Arc};
+
+use super::Engine;
+use crate::MAX_DEPTH;
+
+/// Main thread nodes between summing the helpers' counters for the node budget
+const NODE_LIMIT_CHECK_INTERVAL: u64 = 1024;
+
+impl Engine {
+    #[inline]
+    pub(super) fn is_main_thread(&self) -> bool {
+        self.thread_id == 0
+    }
+
+    /// Matches the helper searchers to the `Threads` option.
+    /// Helpers are recreated when the count changes or the shared tables were replaced.
+    pub(super) fn configure_helpers(&mut self, tables_replaced: bool) {
+        let helper_count = self.config.threads.value.saturating_sub(1);
+
+        if tables_replaced || self.helpers.len() != helper_count {
+            self.helpers.clear();
+            self.helpers = (1..=helper_count)
+                .map(|thread_id| self.create_helper(thread_id))
+                .collect();
+            self.helper_nodes = self
+                .helpers
+                .iter()
+                .map(|helper| Arc::clone(&helper.nodes))
+                .collect();
+            return;
+        }
+
+        for helper in &mut self.helpers {
+            helper.configure(&self.config, false);
+        }
+    }
+
+    fn create_helper(&self, thread_id: usize) -> Engine {
+        let hce = Box::new(hce::Evaluator::new(
+            self.piece_values,
+            self.config.get_hce_config(),
+        ));
+        let nnue = self.nnue.as_ref().map(|nnue| nnue.boxed_clone());
+
+        let mut helper = Engine::with_tables(
+            &self.config,
+            hce,
+            nnue,
+            Arc::clone(&self.stop),
+            Arc::clone(&self.tt),
+            Arc::clone(&self.qs_tt),
+            thread_id,
+        );
+
+        // Not an init: the tables are shared with the main thread and must be kept
+        helper.configure(&self.config, false);
+
+        helper
+    }
+
+    /// Nodes searched by all threads in the current search.
+    pub(super) fn total_nodes(&self) -> u64 {
+        let helper_nodes: u64 = self
+            .helper_nodes
+            .iter()
+            .map(|nodes| nodes.load(Ordering::Relaxed))
+            .sum();
+
+        self.nodes.load(Ordering::Relaxed) + helper_nodes
+    }
+
+    /// Whether the "go nodes" budget is used up. Called by the main thread at every node,
+    /// so the helpers' counters are only summed every `NODE_LIMIT_CHECK_INTERVAL` nodes.
+    pub(super) fn node_limit_reached(&self) -> bool {
+        let Some(limit) = self.node_limit else {
+            return false;
+        };
+
+        let nodes = self.nodes.load(Ordering::Relaxed);
+        nodes >= limit
+            || (nodes.is_multiple_of(NODE_LIMIT_CHECK_INTERVAL) && self.total_nodes() >= limit)
+    }
+
+    /// Iterative deepening for a helper thread. Runs until the main thread stops the search,
+    /// without time control or output - its only job is to fill the shared transposition table.
+    pub(super) fn helper_search(&mut self) {
+        let mut window = self.new_aspiration_window();
+
+        // Odd helpers start one ply deeper so the threads desynchronize
+        let mut depth = 1 + (self.thread_id % 2) as u8;
+        let mut best_score = 0;
+
+        while !self.stop.load(Ordering::Relaxed) && depth <= MAX_DEPTH as u8 {
+            window.begin_depth(depth, best_score);
+
+            if let Some((_, score)) =
+                self.aspiration_search(&mut window, depth, &[], None, &mut || {})
+            {
+                best_score = score;
+            }
+
+            depth += 1;
+        }
+    }
+}
@@ -0,0 +1,7 @@
+/// Check extension: moves that give check are searched deeper so forcing
+/// sequences are not cut off by the horizon.
+///
+/// <https://www.chessprogramming.org/Check_Extensions>
+pub fn applies(gives_check: bool) -> bool {
+    gives_check
+}
@@ -1,7 +1,135 @@
 use cozy_chess::{Board, Move, Piece};
 
+use crate::stack::SearchNode;
+
+mod check;
 mod passed_pawn;
+mod recapture;
+mod single_reply;
+mod singular;
+
+pub use singular::{can_singular_extend, classify, singular_beta, verification_depth, Singularity};
+
+/// Plies added by each extension, and how many plies of extensions a single
+/// path from the root may accumulate.
+#[derive(Clone, Copy)]
+pub struct ExtensionConfig {
+    pub check: u8,
+    pub recapture: u8,
+    pub single_reply: u8,
+    pub passed_pawn: u8,
+    pub budget: u8,
+}
+
+/// The move being extended and the position it is played from.
+pub struct ExtensionContext<'a> {
+    pub board: &'a Board,
+    pub m: Move,
+    pub moved_piece: Piece,
+    pub is_capture: bool,
+    pub gives_check: bool,
+    pub in_check: bool,
+    pub only_move: bool,
+    /// Node of the position the move is played from
+    pub node: &'a SearchNode,
+}
+
+/// Extension for a move, before the path budget is applied.
+/// Extensions don't stack: the largest applicable one is used.
+pub fn get(ctx: &ExtensionContext, config: &ExtensionConfig) -> u8 {
+    let mut extension = 0;
+
+    if check::applies(ctx.gives_check) {
+        extension = extension.max(config.check);
+    }
+    if recapture::applies(&ctx.m, ctx.is_capture, ctx.node) {
+        extension = extension.max(config.recapture);
+    }
+    if single_reply::applies(ctx.in_check, ctx.only_move) {
+        extension = extension.max(config.single_reply);
+    }
+    if passed_pawn::applies(ctx.board, &ctx.m, ctx.moved_piece, ctx.is_capture) {
+        extension = extension.max(config.passed_pawn);
+    }
+
+    extension
+}
+
+/// Limits an extension to what is left of the path budget, so chains of
+/// checks and recaptures can't blow up the search.
+pub fn within_budget(extension: u8, used: u8, budget: u8) -> u8 {
+    extension.min(budget.saturating_sub(used))
+}
+
+#[cfg(test)]
+mod tests {
+    use cozy_chess::{util::parse_uci_move, Square};
+
+    use super::*;
+
+    const CONFIG: ExtensionConfig = ExtensionConfig {
+        check: 1,
+        recapture: 1,
+        single_reply: 1,
+        passed_pawn: 1,
+        budget: 4,
+    };
+
+    fn extension(fen: &str, mv: &str, node: &SearchNode) -> u8 {
+        let board: Board = fen.parse().unwrap();
+        let m = parse_uci_move(&board, mv).unwrap();
+        let mut child = board.clone();
+        child.play(m);
+
+        let ctx = ExtensionContext {
+            board: &board,
+            m,
+            moved_piece: board.piece_on(m.from).unwrap(),
+            is_capture: board.colors(!board.side_to_move()).has(m.to),
+            gives_check: !child.checkers().is_empty(),
+            in_check: !board.checkers().is_empty(),
+            only_move: utils::only_move(&board),
+            node,
+        };
+        get(&ctx, &CONFIG)
+    }
+
+    #[test]
+    fn test_extensions() {
+        let root = SearchNode::new(0);
+
+        // Check
+        assert_eq!(
+            extension("4k3/8/8/8/8/8/8/R3K3 w - - 0 1", "a1a8", &root),
+            1
+        );
+        // Quiet move
+        assert_eq!(
+            extension("4k3/8/8/8/8/8/8/R3K3 w - - 0 1", "a1a7", &root),
+            0
+        );
+        // Only legal reply to a check
+        assert_eq!(extension("k7/8/1K6/8/8/8/8/R7 b - - 0 1", "a8b8", &root), 1);
+
+        // Recapture on the square of the previous capture
+        let fen = "4k3/8/8/3n4/8/8/8/3RK3 w - - 0 1";
+        let knight_takes = Move {
+            from: Square::B6,
+            to: Square::D5,
+            promotion: None,
+        };
+        let capture = SearchNode {
+            is_capture: true,
+            ..SearchNode::with_move(0, knight_takes, Piece::Knight)
+        };
+        assert_eq!(extension(fen, "d1d5", &capture), 1);
+        assert_eq!(extension(fen, "d1d5", &root), 0);
+    }
 
-pub fn get(board: &Board, m: &Move, moved_piece: Piece, is_capture: bool) -> u8 {
-    passed_pawn::extension(board, m, moved_piece, is_capture)
+    #[test]
+    fn test_budget() {
+        assert_eq!(within_budget(1, 0, 4), 1);
+        assert_eq!(within_budget(2, 3, 4), 1);
+        assert_eq!(within_budget(1, 4, 4), 0);
+    }
 }
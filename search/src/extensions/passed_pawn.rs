@@ -1,18 +1,13 @@
 use cozy_chess::{Board, Color, Move, Piece, Rank};
 
-/// Passed pawn extension: returns 1 for pawn pushes to 7th rank, 0 otherwise.
+/// Passed pawn extension: pawn pushes to the 7th rank.
 /// Non-captures only - captures are already tactical.
-pub fn extension(board: &Board, m: &Move, moved_piece: Piece, is_capture: bool) -> u8 {
+pub fn applies(board: &Board, m: &Move, moved_piece: Piece, is_capture: bool) -> bool {
     if moved_piece != Piece::Pawn || is_capture || m.promotion.is_some() {
-        return 0;
+        return false;
     }
-    let is_seventh = match board.side_to_move() {
+    match board.side_to_move() {
         Color::White => m.to.rank() == Rank::Seventh,
         Color::Black => m.to.rank() == Rank::Second,
-    };
-    if is_seventh {
-        1
-    } else {
-        0
     }
 }
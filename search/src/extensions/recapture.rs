@@ -0,0 +1,11 @@
+use cozy_chess::Move;
+
+use crate::stack::SearchNode;
+
+/// Recapture extension: capturing back on the square of the previous capture
+/// usually just restores material, so resolve the exchange before evaluating.
+///
+/// <https://www.chessprogramming.org/Recapture_Extensions>
+pub fn applies(m: &Move, is_capture: bool, previous: &SearchNode) -> bool {
+    is_capture && previous.is_capture && previous.last_move.is_some_and(|last| last.to == m.to)
+}
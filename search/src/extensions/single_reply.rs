@@ -0,0 +1,7 @@
+/// Single reply extension: when in check with only one legal move, the move is forced
+/// and costs no branching, so searching it deeper is cheap.
+///
+/// <https://www.chessprogramming.org/One_Reply_Extensions>
+pub fn applies(in_check: bool, only_move: bool) -> bool {
+    in_check && only_move
+}
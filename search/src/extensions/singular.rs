@@ -0,0 +1,63 @@
+use crate::{pruning::MATE_SCORE_BOUND, transposition::Bound};
+
+/// Outcome of the singular verification search for the TT move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Singularity {
+    /// No other move came close to the TT value: extend the TT move.
+    Singular,
+    /// Another move also beats beta, so the node fails high without searching further.
+    MultiCut(i16),
+    /// Alternatives are about as good: reduce the TT move instead.
+    NotSingular,
+}
+
+/// Singular extensions only pay off when the TT entry is a trustworthy lower bound
+/// from a search not much shallower than the current one.
+pub fn can_singular_extend(
+    remaining_depth: u8,
+    tt_depth: u8,
+    tt_bound: Bound,
+    tt_value: i16,
+    min_depth: u8,
+    tt_depth_margin: u8,
+) -> bool {
+    remaining_depth >= min_depth
+        && tt_bound != Bound::Upper
+        && tt_depth + tt_depth_margin >= remaining_depth
+        && tt_value.abs() < MATE_SCORE_BOUND
+}
+
+/// Score the alternatives must stay below for the TT move to be singular.
+pub fn singular_beta(tt_value: i16, remaining_depth: u8, margin: i16) -> i16 {
+    tt_value - margin * remaining_depth as i16
+}
+
+/// Remaining depth of the verification search.
+pub fn verification_depth(remaining_depth: u8) -> u8 {
+    (remaining_depth - 1) / 2
+}
+
+pub fn classify(value: i16, singular_beta: i16, beta: i16) -> Singularity {
+    if value < singular_beta {
+        Singularity::Singular
+    } else if singular_beta >= beta {
+        Singularity::MultiCut(singular_beta)
+    } else {
+        Singularity::NotSingular
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        // Every alternative fails low against the lowered bound
+        assert_eq!(classify(80, 90, 50), Singularity::Singular);
+        // An alternative reaches the lowered bound, which is itself above beta
+        assert_eq!(classify(95, 90, 50), Singularity::MultiCut(90));
+        // An alternative reaches the lowered bound, but that is below beta
+        assert_eq!(classify(95, 90, 120), Singularity::NotSingular);
+    }
+}
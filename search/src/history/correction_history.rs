@@ -0,0 +1,162 @@
+use cozy_chess::{Board, Color, Piece};
+
+use crate::{pruning::MATE_SCORE_BOUND, EngineConfig};
+
+const TABLE_SIZE: usize = 16384;
+
+/// Entries are stored in 1/GRAIN centipawns, so small errors still accumulate.
+const GRAIN: i32 = 256;
+/// Largest correction a single entry can make (in centipawns)
+const MAX_CORRECTION: i32 = 64;
+const WEIGHT_SCALE: i32 = 1024;
+const MAX_UPDATE_WEIGHT: i32 = 128;
+
+const NON_PAWN_PIECES: [Piece; 5] = [
+    Piece::Knight,
+    Piece::Bishop,
+    Piece::Rook,
+    Piece::Queen,
+    Piece::King,
+];
+
+/// Learns how far the static eval is off from search results for similar positions,
+/// and corrects the static eval by that amount. Positions are grouped by pawn
+/// structure and by each side's non-pawn material, separately per side to move.
+///
+/// <https://www.chessprogramming.org/Static_Evaluation_Correction_History>
+#[derive(Clone)]
+pub struct CorrectionHistory {
+    /// Indexed by [side_to_move][pawn_key]
+    pawn: Vec<i16>,
+    /// Indexed by [side_to_move][color][non_pawn_key]
+    non_pawn: Vec<i16>,
+    /// Percentage each table contributes to the correction
+    pawn_weight: i32,
+    non_pawn_weight: i32,
+}
+
+impl CorrectionHistory {
+    pub fn new(pawn_weight: i32, non_pawn_weight: i32) -> Self {
+        Self {
+            pawn: vec![0; Color::NUM * TABLE_SIZE],
+            non_pawn: vec![0; Color::NUM * Color::NUM * TABLE_SIZE],
+            pawn_weight,
+            non_pawn_weight,
+        }
+    }
+
+    pub fn configure(&mut self, config: &EngineConfig) {
+        self.pawn_weight = config.correction_history_pawn_weight.value;
+        self.non_pawn_weight = config.correction_history_non_pawn_weight.value;
+        self.reset();
+    }
+
+    pub fn matches_config(&self, config: &EngineConfig) -> bool {
+        self.pawn_weight == config.correction_history_pawn_weight.value
+            && self.non_pawn_weight == config.correction_history_non_pawn_weight.value
+    }
+
+    pub fn reset(&mut self) {
+        self.pawn.fill(0);
+        self.non_pawn.fill(0);
+    }
+
+    /// Correction to add to the static eval, from the side to move's perspective.
+    pub fn get(&self, board: &Board) -> i16 {
+        let stm = board.side_to_move();
+
+        let pawn = self.pawn[Self::pawn_index(board)] as i32;
+        let non_pawn: i32 = Color::ALL
+            .iter()
+            .map(|&color| self.non_pawn[Self::non_pawn_index(board, stm, color)] as i32)
+            .sum();
+
+        let correction =
+            (pawn * self.pawn_weight + non_pawn * self.non_pawn_weight) / (100 * GRAIN);
+        correction as i16
+    }
+
+    /// Moves the entries towards the gap between the search score and the static eval
+    /// (both from the side to move's perspective). Deeper searches weigh more.
+    pub fn update(&mut self, board: &Board, remaining_depth: u8, score: i16, static_eval: i16) {
+        if score.abs() >= MATE_SCORE_BOUND {
+            return;
+        }
+
+        let target = (score as i32 - static_eval as i32) * GRAIN;
+        let depth = remaining_depth as i32;
+        let weight = (depth * depth + 2 * depth + 1).min(MAX_UPDATE_WEIGHT);
+
+        let stm = board.side_to_move();
+        Self::blend(&mut self.pawn[Self::pawn_index(board)], target, weight);
+        for color in Color::ALL {
+            let index = Self::non_pawn_index(board, stm, color);
+            Self::blend(&mut self.non_pawn[index], target, weight);
+        }
+    }
+
+    fn blend(entry: &mut i16, target: i32, weight: i32) {
+        let blended = (*entry as i32 * (WEIGHT_SCALE - weight) + target * weight) / WEIGHT_SCALE;
+        let max = MAX_CORRECTION * GRAIN;
+        *entry = blended.clamp(-max, max) as i16;
+    }
+
+    fn pawn_index(board: &Board) -> usize {
+        let stm = board.side_to_move() as usize;
+        stm * TABLE_SIZE + (pawn_key(board) as usize % TABLE_SIZE)
+    }
+
+    fn non_pawn_index(board: &Board, stm: Color, color: Color) -> usize {
+        let table = stm as usize * Color::NUM + color as usize;
+        table * TABLE_SIZE + (non_pawn_key(board, color) as usize % TABLE_SIZE)
+    }
+}
+
+/// Hash of the pawn structure of both sides.
+fn pawn_key(board: &Board) -> u64 {
+    let pawns = board.pieces(Piece::Pawn);
+    let white = (pawns & board.colors(Color::White)).0;
+    let black = (pawns & board.colors(Color::Black)).0;
+    mix(mix(white) ^ black)
+}
+
+/// Hash of the placement of one side's pieces other than pawns.
+fn non_pawn_key(board: &Board, color: Color) -> u64 {
+    let ours = board.colors(color);
+    NON_PAWN_PIECES
+        .iter()
+        .fold(0, |key, &piece| mix(key ^ (board.pieces(piece) & ours).0))
+}
+
+/// SplitMix64 finalizer, spreads bitboard changes over the whole key.
+fn mix(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_learns_eval_error() {
+        let mut history = CorrectionHistory::new(100, 50);
+        let board = Board::default();
+        assert_eq!(history.get(&board), 0);
+
+        // Search keeps finding the position better than the static eval says
+        for _ in 0..50 {
+            history.update(&board, 8, 60, 20);
+        }
+        let correction = history.get(&board);
+        assert!(correction > 0 && correction <= 80, "{}", correction);
+
+        // Same structure with the other side to move is tracked separately
+        let other: Board = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1"
+            .parse()
+            .unwrap();
+        assert_eq!(history.get(&other), 0);
+    }
+}
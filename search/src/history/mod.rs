@@ -1,8 +1,10 @@
 mod capture_history;
 mod continuation_history;
+mod correction_history;
 mod history_heuristic;
 mod utils;
 
 pub use capture_history::CaptureHistory;
 pub use continuation_history::ContinuationHistory;
+pub use correction_history::CorrectionHistory;
 pub use history_heuristic::HistoryHeuristic;
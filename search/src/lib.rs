@@ -4,7 +4,10 @@ mod config;
 pub mod engine;
 mod extensions;
 mod history;
+mod limits;
 mod move_ordering;
+mod observer;
+mod options;
 mod pruning;
 mod stack;
 mod time_control;
@@ -16,3 +19,7 @@ pub const MAX_DEPTH: usize = 100;
 
 pub use config::EngineConfig;
 pub use engine::Engine;
+pub use limits::SearchLimits;
+pub use observer::{ScoreBound, SearchInfo, SearchObserver, SearchScore};
+pub use options::{EngineOption, OptionType};
+pub use time_control::Pondering;
@@ -0,0 +1,32 @@
+use cozy_chess::Move;
+
+/// What to search and when to stop. All times are in milliseconds.
+///
+/// With no limits set the search runs until it is stopped.
+#[derive(Debug, Default, Clone)]
+pub struct SearchLimits {
+    /// Search until stopped, ignoring the clock.
+    pub infinite: bool,
+    /// White's remaining time.
+    pub white_time: Option<u64>,
+    /// Black's remaining time.
+    pub black_time: Option<u64>,
+    /// White's increment per move.
+    pub white_increment: Option<u64>,
+    /// Black's increment per move.
+    pub black_increment: Option<u64>,
+    /// Moves until the next time control (sudden death if not set).
+    pub moves_to_go: Option<u64>,
+    /// Search to this depth only.
+    pub depth: Option<u8>,
+    /// Search this many nodes only.
+    pub nodes: Option<u64>,
+    /// Search for a mate in this many moves.
+    pub mate: Option<u8>,
+    /// Search for exactly this long.
+    pub move_time: Option<u64>,
+    /// Restrict the search to these root moves. Empty means all legal moves.
+    pub search_moves: Vec<Move>,
+    /// Search without time limits until the engine's `Pondering` ends.
+    pub ponder: bool,
+}
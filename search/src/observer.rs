@@ -0,0 +1,112 @@
+use std::time::Duration;
+
+use cozy_chess::Move;
+use evaluation::wdl::Wdl;
+
+/// Score of a search line, from the side to move's point of view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchScore {
+    /// Normalized centipawns: +100 means a 50% chance of winning.
+    Centipawns(i16),
+    /// Mate in N moves. Positive = we mate, negative = we get mated.
+    Mate(i16),
+}
+
+impl Default for SearchScore {
+    fn default() -> Self {
+        SearchScore::Centipawns(0)
+    }
+}
+
+/// Marks a score as a lower (fail-high) or upper (fail-low) bound.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScoreBound {
+    Lower,
+    Upper,
+}
+
+/// Progress of the search, reported for every line after each completed
+/// iteration and whenever an aspiration window fails.
+#[derive(Debug, Default, Clone)]
+pub struct SearchInfo {
+    pub depth: u8,
+    pub sel_depth: u8,
+    /// 1-based index of the line when searching multiple PVs.
+    pub multi_pv: usize,
+    /// Nodes searched by all threads.
+    pub nodes: u64,
+    pub nodes_per_second: u64,
+    /// Transposition table usage in per mille.
+    pub hashfull: u16,
+    pub time: Duration,
+    pub score: SearchScore,
+    /// Set when the score is only a bound, e.g. after an aspiration window failure.
+    pub bound: Option<ScoreBound>,
+    /// Win/draw/loss probabilities, when enabled in the config.
+    pub wdl: Option<Wdl>,
+    /// Principal variation from the root position.
+    pub pv: Vec<Move>,
+}
+
+/// Receives progress events from the main search thread.
+///
+/// All methods default to doing nothing, so observers only implement what they need.
+pub trait SearchObserver {
+    /// A line was searched (or its score bounded) at some depth.
+    fn on_info(&self, _info: &SearchInfo) {}
+
+    /// A root move is about to be searched. `number` is its 1-based index in the move list.
+    /// Only reported once iterations take a noticeable amount of time.
+    fn on_current_move(&self, _depth: u8, _mv: Move, _number: u16) {}
+
+    /// A diagnostic message, sent in debug mode.
+    fn on_message(&self, _message: &str) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::sync::{atomic::AtomicBool, Arc};
+
+    use super::*;
+    use crate::{Engine, EngineConfig, SearchLimits};
+
+    #[derive(Default)]
+    struct Recorder {
+        infos: RefCell<Vec<SearchInfo>>,
+    }
+
+    impl SearchObserver for Recorder {
+        fn on_info(&self, info: &SearchInfo) {
+            self.infos.borrow_mut().push(info.clone());
+        }
+    }
+
+    #[test]
+    fn test_observer_receives_progress() {
+        let mut config = EngineConfig::default();
+        config.hash_size.value = 1;
+        let hce = Box::new(hce::Evaluator::new(
+            config.get_piece_values(),
+            config.get_hce_config(),
+        ));
+        let mut engine = Engine::new(&config, hce, None, Arc::new(AtomicBool::new(false)));
+
+        let limits = SearchLimits {
+            depth: Some(4),
+            ..Default::default()
+        };
+        let recorder = Recorder::default();
+        let (best_move, _) = engine.search(&limits, Some(&recorder)).unwrap();
+
+        let infos = recorder.infos.into_inner();
+        let exact: Vec<_> = infos.iter().filter(|info| info.bound.is_none()).collect();
+        let depths: Vec<u8> = exact.iter().map(|info| info.depth).collect();
+        assert_eq!(depths, vec![1, 2, 3, 4]);
+
+        let last = exact.last().unwrap();
+        assert_eq!(last.multi_pv, 1);
+        assert_eq!(last.pv.first(), Some(&best_move));
+        assert!(last.nodes > 0);
+    }
+}
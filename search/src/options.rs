@@ -0,0 +1,80 @@
+/// Metadata of a configuration parameter that frontends expose as an option.
+/// Each protocol translates it to its own format (e.g. UCI `option` lines).
+#[derive(Debug, Clone)]
+pub struct EngineOption {
+    pub name: &'static str,
+    pub option_type: OptionType,
+}
+
+#[derive(Debug, Clone)]
+pub enum OptionType {
+    Spin {
+        min: i32,
+        max: i32,
+    },
+    Check,
+    /// An action without a value (e.g. "Clear Hash").
+    Button,
+    /// Free text, such as a file path.
+    String,
+    /// One of a fixed set of values.
+    Combo {
+        options: &'static [&'static str],
+    },
+}
+
+impl OptionType {
+    pub fn validate(&self, value: &str) -> Result<(), String> {
+        match self {
+            OptionType::Spin { min, max } => {
+                let parsed = value
+                    .parse::<i32>()
+                    .map_err(|e| format!("Invalid integer: {}", e))?;
+                if parsed < *min || parsed > *max {
+                    return Err(format!("Value {} out of range [{}, {}]", parsed, min, max));
+                }
+                Ok(())
+            }
+            OptionType::Check => match value.to_lowercase().as_str() {
+                "true" | "false" => Ok(()),
+                _ => Err("Boolean value must be 'true' or 'false'".to_string()),
+            },
+            OptionType::Button | OptionType::String => Ok(()),
+            OptionType::Combo { options } => {
+                if options.contains(&value) {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "Value '{}' must be one of: {}",
+                        value,
+                        options.join(", ")
+                    ))
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        let spin = OptionType::Spin { min: 1, max: 10 };
+        assert!(spin.validate("5").is_ok());
+        assert!(spin.validate("11").is_err());
+        assert!(spin.validate("five").is_err());
+
+        assert!(OptionType::Check.validate("TRUE").is_ok());
+        assert!(OptionType::Check.validate("yes").is_err());
+        assert!(OptionType::Button.validate("").is_ok());
+        assert!(OptionType::String.validate("/path/to/file").is_ok());
+
+        let combo = OptionType::Combo {
+            options: &["Auto", "HCE", "NNUE"],
+        };
+        assert!(combo.validate("HCE").is_ok());
+        assert!(combo.validate("Magic").is_err());
+    }
+}
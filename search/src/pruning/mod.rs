@@ -2,6 +2,7 @@ mod aspiration;
 mod futility;
 mod mate_distance;
 mod null_move;
+mod probcut;
 mod reductions;
 
 pub use aspiration::{AspirationWindow, Pass};
@@ -11,4 +12,5 @@ pub use futility::{
 };
 pub use mate_distance::{mate_distance_prune, MATE_SCORE_BOUND};
 pub use null_move::{can_null_move_prune, null_move_reduction};
+pub use probcut::{can_probcut, probcut_beta, tt_refutes_probcut};
 pub use reductions::{iir, lmr, should_lmp_prune};
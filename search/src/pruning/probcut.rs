@@ -0,0 +1,55 @@
+use super::MATE_SCORE_BOUND;
+
+// ProbCut
+// If a good capture beats beta by a margin in a reduced search, the full-depth
+// search would very likely fail high as well, so the node can be cut early.
+pub fn can_probcut(
+    remaining_depth: u8,
+    in_check: bool,
+    is_pv_node: bool,
+    beta: i16,
+    min_depth: u8,
+) -> bool {
+    remaining_depth >= min_depth && !in_check && !is_pv_node && beta.abs() < MATE_SCORE_BOUND
+}
+
+pub fn probcut_beta(beta: i16, margin: i16) -> i16 {
+    beta + margin
+}
+
+// A TT entry from a search nearly as deep as the reduced one that stayed below
+// the raised beta predicts that ProbCut would fail.
+pub fn tt_refutes_probcut(
+    tt_depth: u8,
+    tt_value: i16,
+    remaining_depth: u8,
+    reduction: u8,
+    probcut_beta: i16,
+) -> bool {
+    tt_depth + reduction >= remaining_depth && tt_value < probcut_beta
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_can_probcut() {
+        assert!(can_probcut(6, false, false, 0, 5));
+        assert!(!can_probcut(4, false, false, 0, 5));
+        assert!(!can_probcut(6, true, false, 0, 5));
+        assert!(!can_probcut(6, false, true, 0, 5));
+        assert!(!can_probcut(6, false, false, MATE_SCORE_BOUND, 5));
+        assert!(!can_probcut(6, false, false, -MATE_SCORE_BOUND, 5));
+    }
+
+    #[test]
+    fn test_tt_refutes_probcut() {
+        // Deep enough and below the raised beta
+        assert!(tt_refutes_probcut(2, 100, 6, 4, 200));
+        // Too shallow to trust
+        assert!(!tt_refutes_probcut(1, 100, 6, 4, 200));
+        // Reached the raised beta
+        assert!(!tt_refutes_probcut(2, 200, 6, 4, 200));
+    }
+}
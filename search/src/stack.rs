@@ -1,4 +1,5 @@
 use cozy_chess::{Move, Piece};
+use utils::GameHistory;
 
 /// A node in the search stack, tracking state at each ply.
 #[derive(Clone, Copy)]
@@ -11,6 +12,12 @@ pub struct SearchNode {
     pub piece: Option<Piece>,
     /// Cached static eval (for improving detection)
     pub static_eval: Option<i16>,
+    /// Move skipped while verifying whether the TT move is singular
+    pub excluded_move: Option<Move>,
+    /// Whether the move that led here was a capture (for recapture extensions)
+    pub is_capture: bool,
+    /// Plies of extensions used on the path from the root (bounded by the extension budget)
+    pub extensions: u8,
 }
 
 impl SearchNode {
@@ -20,6 +27,9 @@ impl SearchNode {
             last_move: None,
             piece: None,
             static_eval: None,
+            excluded_move: None,
+            is_capture: false,
+            extensions: 0,
         }
     }
 
@@ -29,6 +39,9 @@ impl SearchNode {
             last_move: Some(mv),
             piece: Some(piece),
             static_eval: None,
+            excluded_move: None,
+            is_capture: false,
+            extensions: 0,
         }
     }
 }
@@ -52,8 +65,20 @@ impl SearchStack {
     pub fn push(&mut self, node: SearchNode) {
         self.nodes.push(node);
     }
-    pub fn push_move(&mut self, hash: u64, mv: Move, piece: Piece) {
-        self.push(SearchNode::with_move(hash, mv, piece));
+    pub fn push_move(&mut self, hash: u64, mv: Move, piece: Piece, is_capture: bool) {
+        self.push(SearchNode {
+            is_capture,
+            ..SearchNode::with_move(hash, mv, piece)
+        });
+    }
+
+    /// Pushes a null move node. Passing keeps the extensions already used on the path.
+    pub fn push_null(&mut self, hash: u64) {
+        let extensions = self.current().extensions;
+        self.push(SearchNode {
+            extensions,
+            ..SearchNode::new(hash)
+        });
     }
 
     pub fn pop(&mut self) -> Option<SearchNode> {
@@ -73,6 +98,15 @@ impl SearchStack {
         }
     }
 
+    /// Move excluded from the search at the current node, if any.
+    pub fn excluded_move(&self) -> Option<Move> {
+        self.nodes.last().and_then(|node| node.excluded_move)
+    }
+
+    pub fn set_excluded_move(&mut self, mv: Option<Move>) {
+        self.current_mut(|node| node.excluded_move = mv);
+    }
+
     /// Returns true if eval improved vs 2 plies ago (same side to move).
     /// Used for more aggressive pruning when position is getting worse.
     pub fn is_improving(&self) -> bool {
@@ -92,19 +126,47 @@ impl SearchStack {
         false
     }
 
-    /// Detects a single repetition and treats it as a draw.
-    /// We don't require threefold because the search tends to cycle once it finds a repetition.
-    pub fn is_repetition(&self, game_history: &ahash::AHashSet<u64>) -> bool {
-        let current_hash = self.nodes[self.nodes.len() - 1].hash;
+    /// Detects a repetition that should be scored as a draw.
+    ///
+    /// A position repeated within the search tree (after the root) is a draw right away,
+    /// since the side that allowed it can repeat again. A position that only repeats the
+    /// root or the game before it needs to be a threefold repetition.
+    /// Only the last `halfmove_clock` plies are scanned, as an irreversible move
+    /// rules out any earlier repetition, and the scan stops at a null move.
+    pub fn is_repetition(&self, game_history: &GameHistory, halfmove_clock: u8) -> bool {
+        let ply = self.nodes.len() - 1;
+        let current_hash = self.nodes[ply].hash;
+        let mut earlier_occurrences = 0;
 
-        // Check if this position was seen in the game before we started searching
-        if game_history.contains(&current_hash) {
-            return true;
-        }
+        // Null move nodes are the only ones after the root without a move
+        let plies_from_null = self.nodes[1..]
+            .iter()
+            .rev()
+            .position(|node| node.last_move.is_none())
+            .unwrap_or(usize::MAX);
+        let max_distance = (halfmove_clock as usize).min(plies_from_null);
 
-        // Check search path (skip current position)
-        for node in self.nodes.iter().rev().skip(1) {
-            if node.hash == current_hash {
+        // Same side to move, so only every other ply can repeat
+        for distance in (4..=max_distance).step_by(2) {
+            let hash = if distance <= ply {
+                self.nodes[ply - distance].hash
+            } else {
+                match game_history.plies_ago(distance - ply) {
+                    Some(hash) => hash,
+                    None => break,
+                }
+            };
+
+            if hash != current_hash {
+                continue;
+            }
+
+            if distance < ply {
+                return true;
+            }
+
+            earlier_occurrences += 1;
+            if earlier_occurrences >= 2 {
                 return true;
             }
         }
@@ -141,3 +203,64 @@ impl SearchStack {
         base_penalty * (1 << consecutive_count)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use cozy_chess::{util::parse_uci_move, Board};
+
+    use super::*;
+
+    /// Plays `moves` from `board`, pushing each position onto `stack`.
+    fn push_moves(stack: &mut SearchStack, board: &mut Board, moves: &str) {
+        for mv in moves.split_whitespace() {
+            let mv = parse_uci_move(board, mv).unwrap();
+            let piece = board.piece_on(mv.from).unwrap();
+            let is_capture = utils::is_capture(board, mv);
+            board.play(mv);
+            stack.push_move(board.hash(), mv, piece, is_capture);
+        }
+    }
+
+    /// Plays `moves` as the game before the root.
+    fn game(moves: &str) -> (Board, GameHistory) {
+        let mut board = Board::default();
+        let mut history = GameHistory::new();
+        for mv in moves.split_whitespace() {
+            let mv = parse_uci_move(&board, mv).unwrap();
+            history.play(&mut board, mv);
+        }
+        (board, history)
+    }
+
+    fn root_stack(board: &Board) -> SearchStack {
+        let mut stack = SearchStack::with_capacity(16);
+        stack.push(SearchNode::new(board.hash()));
+        stack
+    }
+
+    #[test]
+    fn test_repetition_inside_tree_is_draw() {
+        let (mut board, history) = game("g1f3");
+        let mut stack = root_stack(&board);
+        push_moves(&mut stack, &mut board, "g8f6 f3g1 f6g8 g1f3 g8f6");
+
+        assert!(stack.is_repetition(&history, board.halfmove_clock()));
+    }
+
+    #[test]
+    fn test_repeating_root_needs_threefold() {
+        let (mut board, history) = game("");
+        let mut stack = root_stack(&board);
+        push_moves(&mut stack, &mut board, "g1f3 g8f6 f3g1 f6g8");
+
+        // Second occurrence of the start position
+        assert!(!stack.is_repetition(&history, board.halfmove_clock()));
+
+        let (mut board, history) = game("g1f3 g8f6 f3g1 f6g8");
+        let mut stack = root_stack(&board);
+        push_moves(&mut stack, &mut board, "g1f3 g8f6 f3g1 f6g8");
+
+        // Third occurrence
+        assert!(stack.is_repetition(&history, board.halfmove_clock()));
+    }
+}
@@ -4,11 +4,14 @@
 //! - **target**: soft limit, aim to stop here (can be adjusted during search)
 //! - **hard**: absolute maximum, never exceed
 
+use std::fmt;
+
 use cozy_chess::{Board, Color};
 
-use uci::commands::GoParams;
 use utils::only_move;
 
+use crate::SearchLimits;
+
 use super::stats::{TimeControlStats, MIN_DEPTH_FOR_ADJUSTMENTS};
 
 // Time management constants
@@ -40,14 +43,14 @@ pub enum TimeBudget {
 }
 
 impl TimeBudget {
-    pub fn new(params: &GoParams, board: &Board, move_overhead_ms: u64) -> Option<Self> {
+    pub fn new(limits: &SearchLimits, board: &Board, move_overhead_ms: u64) -> Option<Self> {
         // UCI movetime: spend exactly this exact amount
-        if let Some(move_time) = params.move_time {
+        if let Some(move_time) = limits.move_time {
             return Some(Self::Exact { millis: move_time });
         }
 
         // If a depth is set then we do not want this to be timed.
-        if params.depth.is_some() {
+        if limits.depth.is_some() {
             return None;
         }
 
@@ -60,13 +63,13 @@ impl TimeBudget {
 
         let side_to_move = board.side_to_move();
 
-        let time_left = get_time_left(params, side_to_move)?;
-        let increment = get_increment(params, side_to_move);
-        let opponent_time = get_time_left(params, !side_to_move);
+        let time_left = get_time_left(limits, side_to_move)?;
+        let increment = get_increment(limits, side_to_move);
+        let opponent_time = get_time_left(limits, !side_to_move);
 
-        let moves_left = params.moves_to_go.unwrap_or(move_margin(board));
+        let moves_left = limits.moves_to_go.unwrap_or(move_margin(board));
 
-        let reserve = if params.moves_to_go.is_some() {
+        let reserve = if limits.moves_to_go.is_some() {
             // Skip reserving time when we know how many moves to the next refill.
             0
         } else {
@@ -147,19 +150,30 @@ impl TimeBudget {
     }
 }
 
+impl fmt::Display for TimeBudget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TimeBudget::Exact { millis } => write!(f, "exact {}ms", millis),
+            TimeBudget::Managed { target, hard } => {
+                write!(f, "target {}ms hard {}ms", target, hard)
+            }
+        }
+    }
+}
+
 #[inline]
-fn get_time_left(params: &GoParams, color: Color) -> Option<u64> {
+fn get_time_left(limits: &SearchLimits, color: Color) -> Option<u64> {
     match color {
-        Color::White => params.wtime,
-        Color::Black => params.btime,
+        Color::White => limits.white_time,
+        Color::Black => limits.black_time,
     }
 }
 
 #[inline]
-fn get_increment(params: &GoParams, color: Color) -> u64 {
+fn get_increment(limits: &SearchLimits, color: Color) -> u64 {
     match color {
-        Color::White => params.winc.unwrap_or(0),
-        Color::Black => params.binc.unwrap_or(0),
+        Color::White => limits.white_increment.unwrap_or(0),
+        Color::Black => limits.black_increment.unwrap_or(0),
     }
 }
 
@@ -1,12 +1,14 @@
 use std::sync::Arc;
-use std::thread;
 use std::time::Duration;
 
 use cozy_chess::{Board, Move};
-use uci::commands::GoParams;
+use evaluation::scores::MATE_VALUE;
 
 use crate::time_control::budget::TimeBudget;
+use crate::time_control::ponder::Pondering;
 use crate::time_control::stats::TimeControlStats;
+use crate::time_control::timer::SearchTimer;
+use crate::SearchLimits;
 
 // To predict the duration of the next iteration based on the previous one.
 // Assumes next iteration takes ~2x longer than the previous.
@@ -16,21 +18,35 @@ pub struct SearchController {
     start_time: std::time::Instant,
     time_budget: Option<TimeBudget>,
     max_depth: Option<u8>,
-    timer_handle: Option<thread::JoinHandle<()>>,
+    /// Depth cap for reduced playing strength. Unlike "go depth", time limits still apply.
+    depth_cap: Option<u8>,
+    max_nodes: Option<u64>,
+    /// Stop once a mate in this many moves (or faster) is proven
+    mate_moves: Option<u8>,
+    mate_found: bool,
+    /// Stops the search at the hard limit. Cancelled when the controller is dropped.
+    timer: SearchTimer,
     on_stop_callback: Option<Arc<dyn Fn() + Send + Sync>>,
+    /// Active while pondering; time limits only apply once it ends (ponderhit).
+    pondering: Option<Pondering>,
     last_iteration_duration_ms: Option<u64>,
     current_iteration_start_ms: Option<u64>,
     stats: TimeControlStats,
 }
 
 impl SearchController {
-    pub fn new(params: &GoParams, board: &Board, move_overhead_ms: u64) -> Self {
+    pub fn new(limits: &SearchLimits, board: &Board, move_overhead_ms: u64) -> Self {
         Self {
             start_time: std::time::Instant::now(),
-            time_budget: TimeBudget::new(params, board, move_overhead_ms),
-            max_depth: params.depth,
-            timer_handle: None,
+            time_budget: TimeBudget::new(limits, board, move_overhead_ms),
+            max_depth: limits.depth,
+            depth_cap: None,
+            max_nodes: limits.nodes,
+            mate_moves: limits.mate,
+            mate_found: false,
+            timer: SearchTimer::new(),
             on_stop_callback: None,
+            pondering: None,
             last_iteration_duration_ms: None,
             current_iteration_start_ms: None,
             stats: TimeControlStats::new(),
@@ -44,6 +60,23 @@ impl SearchController {
         self.on_stop_callback = Some(Arc::new(callback));
     }
 
+    /// Searches without time limits until `pondering` ends.
+    /// Time spent pondering counts towards the budget, as the search keeps its progress.
+    pub fn on_ponder(&mut self, pondering: Pondering) {
+        self.pondering = Some(pondering);
+    }
+
+    fn is_pondering(&self) -> bool {
+        self.pondering.as_ref().is_some_and(Pondering::is_active)
+    }
+
+    /// Blocks until ponderhit or stop end pondering. Returns immediately if not pondering.
+    pub fn wait_for_ponderhit(&self) {
+        if let Some(pondering) = &self.pondering {
+            pondering.wait();
+        }
+    }
+
     pub fn start_timer(&mut self) {
         let Some(budget) = self.time_budget else {
             return;
@@ -52,23 +85,54 @@ impl SearchController {
             return;
         };
 
-        let duration = Duration::from_millis(budget.hard_limit());
+        let deadline = self.start_time + Duration::from_millis(budget.hard_limit());
         let callback = Arc::clone(callback);
 
-        let handle = thread::spawn(move || {
-            thread::sleep(duration);
-            callback();
-        });
+        self.timer
+            .start(deadline, self.pondering.clone(), move || callback());
+    }
 
-        self.timer_handle = Some(handle);
+    /// Human-readable time budget, for debug output.
+    pub fn describe_budget(&self) -> String {
+        match self.time_budget {
+            Some(budget) => budget.to_string(),
+            None => "unlimited".to_string(),
+        }
+    }
+
+    /// Caps the depth and tightens the node limit (e.g. for reduced playing strength).
+    pub fn limit_search(&mut self, depth_cap: u8, max_nodes: u64) {
+        self.depth_cap = Some(depth_cap);
+        self.max_nodes = Some(
+            self.max_nodes
+                .map_or(max_nodes, |nodes| nodes.min(max_nodes)),
+        );
+    }
+
+    /// Node budget for "go nodes", to be checked by the search itself.
+    pub fn node_limit(&self) -> Option<u64> {
+        self.max_nodes
     }
 
     pub fn should_continue_to_next_depth(&self, next_depth: u8) -> bool {
+        if self.mate_found {
+            return false;
+        }
+
+        if self.depth_cap.is_some_and(|cap| next_depth > cap) {
+            return false;
+        }
+
         // Depth check (if specified)
         if let Some(max_depth) = self.max_depth {
             return next_depth <= max_depth;
         }
 
+        // The clock is not ours until ponderhit
+        if self.is_pondering() {
+            return true;
+        }
+
         // Always allow the first iterations regardless of time gates.
         // Ensures we can produce at least one best move under extreme low time.
         if next_depth <= 2 {
@@ -135,6 +199,11 @@ impl SearchController {
     pub fn on_iteration_complete(&mut self, depth: u8, score: i16, best_move: Option<Move>) {
         self.stats.add_iteration(depth, score, best_move);
 
+        if let Some(moves) = self.mate_moves {
+            let mate_plies = 2 * moves as i16 - 1;
+            self.mate_found = score >= MATE_VALUE - mate_plies;
+        }
+
         if let Some(ref mut budget) = self.time_budget {
             budget.adjust_for_search_behavior(&self.stats);
         }
@@ -144,15 +213,37 @@ impl SearchController {
         self.stats.add_aspiration_failure();
     }
 
+    /// Cancels the hard limit timer. Once this returns, the stop callback won't be called.
     pub fn stop_timer(&mut self) {
-        if let Some(handle) = self.timer_handle.take() {
-            std::mem::drop(handle);
-        }
+        self.timer.cancel();
     }
 }
 
-impl Drop for SearchController {
-    fn drop(&mut self) {
-        self.stop_timer();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_depth_cap_keeps_time_limits() {
+        let limits = SearchLimits {
+            move_time: Some(0),
+            ..Default::default()
+        };
+        let mut controller = SearchController::new(&limits, &Board::default(), 0);
+        controller.limit_search(10, u64::MAX);
+
+        // The clock still ends the search below the cap
+        assert!(controller.should_continue_to_next_depth(2));
+        assert!(!controller.should_continue_to_next_depth(3));
+
+        let limits = SearchLimits {
+            depth: Some(20),
+            ..Default::default()
+        };
+        let mut controller = SearchController::new(&limits, &Board::default(), 0);
+        controller.limit_search(4, u64::MAX);
+
+        assert!(controller.should_continue_to_next_depth(4));
+        assert!(!controller.should_continue_to_next_depth(5));
     }
 }
@@ -1,5 +1,8 @@
 mod budget;
 mod controller;
+mod ponder;
 mod stats;
+mod timer;
 
 pub use controller::SearchController;
+pub use ponder::Pondering;
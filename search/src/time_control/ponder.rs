@@ -0,0 +1,73 @@
+use std::sync::{Arc, Condvar, Mutex};
+
+#[derive(Default)]
+struct Shared {
+    active: Mutex<bool>,
+    changed: Condvar,
+}
+
+/// Pondering state of a "go ponder" search, shared between the frontend and the search.
+///
+/// The frontend starts it on "go ponder" and ends it on ponderhit or stop.
+/// Ending it wakes everything waiting for it, so the search doesn't have to poll.
+#[derive(Clone, Default)]
+pub struct Pondering {
+    shared: Arc<Shared>,
+}
+
+impl Pondering {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn start(&self) {
+        *self.shared.active.lock().unwrap() = true;
+    }
+
+    /// Ends pondering (ponderhit or stop) and wakes up the waiting search.
+    pub fn end(&self) {
+        *self.shared.active.lock().unwrap() = false;
+        self.shared.changed.notify_all();
+    }
+
+    pub fn is_active(&self) -> bool {
+        *self.shared.active.lock().unwrap()
+    }
+
+    /// Blocks until pondering ends. Returns immediately if not pondering.
+    pub fn wait(&self) {
+        let active = self.shared.active.lock().unwrap();
+        drop(
+            self.shared
+                .changed
+                .wait_while(active, |active| *active)
+                .unwrap(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_end_wakes_waiter() {
+        let pondering = Pondering::new();
+        pondering.start();
+
+        let waiter = {
+            let pondering = pondering.clone();
+            thread::spawn(move || pondering.wait())
+        };
+
+        thread::sleep(Duration::from_millis(20));
+        assert!(!waiter.is_finished());
+
+        pondering.end();
+        waiter.join().unwrap();
+        assert!(!pondering.is_active());
+    }
+}
@@ -0,0 +1,142 @@
+//! Cancellable timer that stops the search at the hard time limit.
+//!
+//! Every `start` and `cancel` bumps a generation counter, and the timer thread
+//! only fires if its generation is still current. The callback runs while
+//! holding the lock, so once `cancel` returns a timer can no longer fire -
+//! a search that ended early can't stop the next one.
+
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::ponder::Pondering;
+
+// How often to check for ponderhit while pondering.
+const PONDER_POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+#[derive(Default)]
+struct Shared {
+    generation: Mutex<u64>,
+    changed: Condvar,
+}
+
+#[derive(Default)]
+pub struct SearchTimer {
+    shared: Arc<Shared>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+impl SearchTimer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Calls `callback` at `deadline`, unless cancelled first. While `pondering`
+    /// is active the deadline is not enforced; once it ends the timer fires at
+    /// the deadline, or right away if it has already passed.
+    pub fn start<F>(&mut self, deadline: Instant, pondering: Option<Pondering>, callback: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.cancel();
+
+        let token = {
+            let mut generation = self.shared.generation.lock().unwrap();
+            *generation += 1;
+            *generation
+        };
+        let shared = Arc::clone(&self.shared);
+
+        self.handle = Some(thread::spawn(move || {
+            let mut generation = shared.generation.lock().unwrap();
+
+            loop {
+                if *generation != token {
+                    return;
+                }
+
+                let wait = if pondering.as_ref().is_some_and(Pondering::is_active) {
+                    PONDER_POLL_INTERVAL
+                } else {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    if remaining.is_zero() {
+                        break;
+                    }
+                    remaining
+                };
+
+                generation = shared.changed.wait_timeout(generation, wait).unwrap().0;
+            }
+
+            // Still holding the lock, so a concurrent cancel waits for this
+            callback();
+        }));
+    }
+
+    /// Cancels the pending timer, if any. Guarantees its callback won't run after this returns.
+    pub fn cancel(&mut self) {
+        *self.shared.generation.lock().unwrap() += 1;
+        self.shared.changed.notify_all();
+
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for SearchTimer {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use super::*;
+
+    fn flag_timer(delay: Duration, fired: &Arc<AtomicBool>) -> SearchTimer {
+        let mut timer = SearchTimer::new();
+        let fired = Arc::clone(fired);
+        timer.start(Instant::now() + delay, None, move || {
+            fired.store(true, Ordering::Relaxed)
+        });
+        timer
+    }
+
+    #[test]
+    fn test_timer_fires() {
+        let fired = Arc::new(AtomicBool::new(false));
+        let _timer = flag_timer(Duration::from_millis(10), &fired);
+
+        thread::sleep(Duration::from_millis(200));
+        assert!(fired.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn test_cancelled_timer_does_not_fire() {
+        let fired = Arc::new(AtomicBool::new(false));
+        let mut timer = flag_timer(Duration::from_millis(50), &fired);
+        timer.cancel();
+
+        thread::sleep(Duration::from_millis(100));
+        assert!(!fired.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn test_restart_replaces_pending_timer() {
+        let first = Arc::new(AtomicBool::new(false));
+        let second = Arc::new(AtomicBool::new(false));
+
+        let mut timer = flag_timer(Duration::from_millis(20), &first);
+        let flag = Arc::clone(&second);
+        timer.start(Instant::now() + Duration::from_secs(60), None, move || {
+            flag.store(true, Ordering::Relaxed)
+        });
+
+        thread::sleep(Duration::from_millis(100));
+        assert!(!first.load(Ordering::Relaxed));
+        assert!(!second.load(Ordering::Relaxed));
+    }
+}
@@ -1,6 +1,7 @@
 use std::mem::size_of;
 use std::simd::prelude::SimdPartialEq;
-use std::simd::u32x4;
+use std::simd::u64x4;
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
 
 use cozy_chess::{Move, Piece, Square};
 use utils::memory::prefetch;
@@ -35,60 +36,103 @@ pub struct ProbeResult {
     pub depth: u8,
 }
 
-/// A single TT entry (16 bytes, fits 4 per cache line).
+/// Decoded contents of a TT entry.
 #[derive(Clone, Copy, Default)]
-#[repr(C)]
-pub struct TTEntry {
-    /// Lower 32 bits of Zobrist hash for verification
-    pub key: u32,
+struct TTData {
     /// Score from searching this position
-    pub value: i16,
-    /// Indicates whether the stored value is exact or a bound
-    pub bound: Bound,
+    value: i16,
     /// Static eval without search, cached to avoid recomputation (i16::MIN = unknown)
-    pub static_eval: i16,
-    /// Search depth that produced this result
-    pub depth: u8,
+    static_eval: i16,
     /// Best move found, packed as: [15:12]=promo, [11:6]=to, [5:0]=from
-    pub best_move_packed: u16,
-    /// Age for replacement policy
-    pub generation: u8,
+    best_move_packed: u16,
+    /// Search depth that produced this result
+    depth: u8,
+    /// Indicates whether the stored value is exact or a bound
+    bound: Bound,
+    /// Age for replacement policy (6 bits)
+    generation: u8,
+}
+
+impl TTData {
+    /// Packs as: [63:58]=generation, [57:56]=bound, [55:48]=depth, [47:32]=move,
+    /// [31:16]=static_eval, [15:0]=value
+    fn pack(&self) -> u64 {
+        (self.value as u16 as u64)
+            | ((self.static_eval as u16 as u64) << 16)
+            | ((self.best_move_packed as u64) << 32)
+            | ((self.depth as u64) << 48)
+            | ((self.bound as u64) << 56)
+            | (((self.generation & GENERATION_MASK) as u64) << 58)
+    }
+
+    fn unpack(data: u64) -> Self {
+        Self {
+            value: data as u16 as i16,
+            static_eval: (data >> 16) as u16 as i16,
+            best_move_packed: (data >> 32) as u16,
+            depth: (data >> 48) as u8,
+            bound: match (data >> 56) & 0x3 {
+                1 => Bound::Lower,
+                2 => Bound::Upper,
+                _ => Bound::Exact,
+            },
+            generation: (data >> 58) as u8 & GENERATION_MASK,
+        }
+    }
+}
+
+/// A single TT entry (16 bytes, fits 4 per cache line).
+///
+/// Entries are shared between search threads without locking. The key word stores
+/// `hash ^ data`, so a torn write (key from one store, data from another) fails
+/// verification on probe instead of returning another position's data.
+///
+/// <https://www.chessprogramming.org/Shared_Hash_Table#Lockless>
+#[derive(Default)]
+#[repr(C)]
+struct TTEntry {
+    key: AtomicU64,
+    data: AtomicU64,
 }
 
 impl TTEntry {
-    #[allow(clippy::too_many_arguments)]
-    pub fn set(
-        &mut self,
-        key: u32,
-        depth: u8,
-        value: i16,
-        static_eval: i16,
-        bound: Bound,
-        best_move_packed: u16,
-        generation: u8,
-    ) {
-        self.key = key;
-        self.depth = depth;
-        self.value = value;
-        self.static_eval = static_eval;
-        self.bound = bound;
-        self.best_move_packed = best_move_packed;
-        self.generation = generation;
+    #[inline]
+    fn load(&self) -> (u64, u64) {
+        (
+            self.key.load(Ordering::Relaxed),
+            self.data.load(Ordering::Relaxed),
+        )
+    }
+
+    #[inline]
+    fn set(&self, hash: u64, data: TTData) {
+        let packed = data.pack();
+        self.key.store(hash ^ packed, Ordering::Relaxed);
+        self.data.store(packed, Ordering::Relaxed);
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        let (key, data) = self.load();
+        key == 0 && data == 0
     }
 }
 
 const CLUSTER_SIZE: usize = 4;
 const MIN_BUCKETS: usize = 1024;
+const GENERATION_MASK: u8 = 0x3F;
 
 /// Hash table for memoizing search results.
 /// Uses 4-entry clusters for cache efficiency and SIMD probing.
 /// Replacement considers depth, age, and bound type.
 ///
+/// The table is lock-free and can be shared between search threads (Lazy SMP).
+///
 /// <https://www.chessprogramming.org/Transposition_Table>
 pub struct TranspositionTable {
     entries: Vec<TTEntry>,
     buckets: usize,
-    generation: u8,
+    generation: AtomicU8,
 }
 
 impl TranspositionTable {
@@ -101,26 +145,33 @@ impl TranspositionTable {
         let buckets = (max_entries / CLUSTER_SIZE).max(MIN_BUCKETS);
         let total_entries = buckets * CLUSTER_SIZE;
 
+        let mut entries = Vec::with_capacity(total_entries);
+        entries.resize_with(total_entries, TTEntry::default);
+
         Self {
-            entries: vec![TTEntry::default(); total_entries],
+            entries,
             buckets,
-            generation: 0,
+            generation: AtomicU8::new(0),
         }
     }
 
-    pub fn clear(&mut self) {
-        // Clear TT entries
-        unsafe {
-            let ptr = self.entries.as_mut_ptr() as *mut u8;
-            let size = self.entries.len() * size_of::<TTEntry>();
-            std::ptr::write_bytes(ptr, 0, size);
+    pub fn clear(&self) {
+        for entry in &self.entries {
+            entry.key.store(0, Ordering::Relaxed);
+            entry.data.store(0, Ordering::Relaxed);
         }
-        self.generation = 0;
+        self.generation.store(0, Ordering::Relaxed);
     }
 
     /// Increments generation counter. Called at start of each search.
-    pub fn age(&mut self) {
-        self.generation = self.generation.wrapping_add(1);
+    pub fn age(&self) {
+        let next = self.generation().wrapping_add(1) & GENERATION_MASK;
+        self.generation.store(next, Ordering::Relaxed);
+    }
+
+    #[inline]
+    fn generation(&self) -> u8 {
+        self.generation.load(Ordering::Relaxed)
     }
 
     /// Returns hash table fill rate in permille (0-1000).
@@ -133,8 +184,8 @@ impl TranspositionTable {
         let sample_size = self.entries.len().min(MAX_SAMPLE);
         let sample = &self.entries[..sample_size];
 
-        // Count non-empty entries (key == 0)
-        let filled_count = sample.iter().filter(|e| e.key != 0).count();
+        // Count non-empty entries
+        let filled_count = sample.iter().filter(|e| !e.is_empty()).count();
 
         // Convert to permille: (filled / sample_size) * 1000
         let permille = (filled_count * 1000) / sample_size;
@@ -158,34 +209,30 @@ impl TranspositionTable {
     pub fn probe(&self, hash: u64, depth: u8) -> Option<ProbeResult> {
         let idx = (hash as usize) % self.buckets;
         let base = idx * CLUSTER_SIZE;
-        let key32 = hash as u32;
-
-        let cluster = &self.entries[base..base + 4];
-        let keys = u32x4::from_array([
-            cluster[0].key,
-            cluster[1].key,
-            cluster[2].key,
-            cluster[3].key,
-        ]);
-        let target_keys = u32x4::splat(key32);
-        let key_matches = keys.simd_eq(target_keys);
+
+        let cluster = &self.entries[base..base + CLUSTER_SIZE];
+        let loaded = [
+            cluster[0].load(),
+            cluster[1].load(),
+            cluster[2].load(),
+            cluster[3].load(),
+        ];
+        let keys = u64x4::from_array(loaded.map(|(key, _)| key));
+        let data = u64x4::from_array(loaded.map(|(_, data)| data));
+        let key_matches = (keys ^ data).simd_eq(u64x4::splat(hash));
 
         // Find deepest matching entry
-        let mut best: Option<(usize, u8)> = None;
-        for (i, entry) in cluster.iter().enumerate() {
+        let mut best: Option<TTData> = None;
+        for (i, &(_, packed)) in loaded.iter().enumerate() {
             if key_matches.test(i) {
-                if let Some((_, d)) = best {
-                    if entry.depth > d {
-                        best = Some((i, entry.depth));
-                    }
-                } else {
-                    best = Some((i, entry.depth));
+                let entry = TTData::unpack(packed);
+                if best.is_none_or(|b| entry.depth > b.depth) {
+                    best = Some(entry);
                 }
             }
         }
 
-        let (i, _) = best?;
-        let entry = &cluster[i];
+        let entry = best?;
 
         // Adjust mate scores relative to current depth
         let value = if entry.value.abs() >= MATE_SCORE_BOUND {
@@ -216,7 +263,7 @@ impl TranspositionTable {
     /// Stores a search result using depth/age-based replacement.
     #[allow(clippy::too_many_arguments)]
     pub fn store(
-        &mut self,
+        &self,
         hash: u64,
         depth: u8,
         max_depth: u8,
@@ -227,8 +274,6 @@ impl TranspositionTable {
         best_move: Option<Move>,
     ) {
         let stored_depth = max_depth - depth;
-        let best_move_packed = pack_move(best_move);
-        let key32 = hash as u32;
 
         let bound = if value <= alpha {
             Bound::Upper
@@ -248,14 +293,22 @@ impl TranspositionTable {
         } else {
             value
         };
-        let stored_se = static_eval.unwrap_or(i16::MIN);
+
+        let current_gen = self.generation();
+        let new_entry = TTData {
+            value: stored_value,
+            static_eval: static_eval.unwrap_or(i16::MIN),
+            best_move_packed: pack_move(best_move),
+            depth: stored_depth,
+            bound,
+            generation: current_gen,
+        };
 
         let idx = (hash as usize) % self.buckets;
         let base = idx * CLUSTER_SIZE;
         let end = base + CLUSTER_SIZE;
 
-        let cluster = &mut self.entries[base..end];
-        let current_gen = self.generation;
+        let cluster = &self.entries[base..end];
 
         // Depth bonus for valuable bound types (exact/lower more useful than upper)
         let depth_bonus = |b: Bound| -> i16 {
@@ -266,43 +319,29 @@ impl TranspositionTable {
         };
 
         // Exact key hit: Replace only if deeper or better bound
-        for e in cluster.iter_mut() {
-            if e.key == key32 {
+        for e in cluster.iter() {
+            let (key, packed) = e.load();
+            if key ^ packed == hash {
+                let old = TTData::unpack(packed);
                 let new_value = stored_depth as i16 + depth_bonus(bound);
-                let old_value = e.depth as i16 + depth_bonus(e.bound);
+                let old_value = old.depth as i16 + depth_bonus(old.bound);
 
                 // Always replace if new bound is Exact and old isn't.
                 // Otherwise, only replace if new entry is deeper or better bound type
                 let should_replace =
-                    (bound == Bound::Exact && e.bound != Bound::Exact) || new_value >= old_value;
+                    (bound == Bound::Exact && old.bound != Bound::Exact) || new_value >= old_value;
 
                 if should_replace {
-                    e.set(
-                        key32,
-                        stored_depth,
-                        stored_value,
-                        stored_se,
-                        bound,
-                        best_move_packed,
-                        current_gen,
-                    );
+                    e.set(hash, new_entry);
                 }
                 return;
             }
         }
 
         // Empty slot
-        for e in cluster.iter_mut() {
-            if e.key == 0 {
-                e.set(
-                    key32,
-                    stored_depth,
-                    stored_value,
-                    stored_se,
-                    bound,
-                    best_move_packed,
-                    current_gen,
-                );
+        for e in cluster.iter() {
+            if e.is_empty() {
+                e.set(hash, new_entry);
                 return;
             }
         }
@@ -311,8 +350,9 @@ impl TranspositionTable {
         let mut victim_idx = 0;
         let mut min_score = i16::MAX;
 
-        for (i, entry) in cluster.iter().enumerate() {
-            let age = current_gen.wrapping_sub(entry.generation) as i16;
+        for (i, e) in cluster.iter().enumerate() {
+            let entry = TTData::unpack(e.data.load(Ordering::Relaxed));
+            let age = (current_gen.wrapping_sub(entry.generation) & GENERATION_MASK) as i16;
             let entry_depth = entry.depth as i16 + depth_bonus(entry.bound);
 
             // Lower score = better candidate for replacement
@@ -324,15 +364,7 @@ impl TranspositionTable {
             }
         }
 
-        cluster[victim_idx].set(
-            key32,
-            stored_depth,
-            stored_value,
-            stored_se,
-            bound,
-            best_move_packed,
-            current_gen,
-        );
+        cluster[victim_idx].set(hash, new_entry);
     }
 }
 
@@ -411,4 +443,49 @@ mod tests {
         assert_eq!(pack_move(None), 0);
         assert_eq!(unpack_move(0), None);
     }
+
+    #[test]
+    fn test_entry_data_roundtrip() {
+        let data = TTData {
+            value: -1234,
+            static_eval: i16::MIN,
+            best_move_packed: 0xABCD,
+            depth: 42,
+            bound: Bound::Upper,
+            generation: 63,
+        };
+        let unpacked = TTData::unpack(data.pack());
+
+        assert_eq!(unpacked.value, data.value);
+        assert_eq!(unpacked.static_eval, data.static_eval);
+        assert_eq!(unpacked.best_move_packed, data.best_move_packed);
+        assert_eq!(unpacked.depth, data.depth);
+        assert!(unpacked.bound == data.bound);
+        assert_eq!(unpacked.generation, data.generation);
+    }
+
+    #[test]
+    fn test_store_and_probe() {
+        let tt = TranspositionTable::new(1);
+        let hash = 0x1234_5678_9ABC_DEF0;
+        let mv = Move {
+            from: "e2".parse().unwrap(),
+            to: "e4".parse().unwrap(),
+            promotion: None,
+        };
+
+        assert!(tt.probe(hash, 0).is_none());
+
+        tt.store(hash, 0, 5, 37, Some(12), -100, 100, Some(mv));
+        let result = tt.probe(hash, 0).unwrap();
+
+        assert_eq!(result.value, 37);
+        assert!(result.bound == Bound::Exact);
+        assert_eq!(result.best_move, Some(mv));
+        assert_eq!(result.static_eval, Some(12));
+        assert_eq!(result.depth, 5);
+
+        // A different position mapping to the same bucket must not match
+        assert!(tt.probe(hash ^ (1 << 63), 0).is_none());
+    }
 }
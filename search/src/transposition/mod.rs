@@ -1,5 +1,5 @@
 mod main;
 mod quiescence;
 
-pub use main::{Bound, TranspositionTable};
+pub use main::{Bound, ProbeResult, TranspositionTable};
 pub use quiescence::QSTable;
@@ -1,6 +1,7 @@
 use std::mem::size_of;
 use std::simd::prelude::SimdPartialEq;
 use std::simd::u32x4;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 use utils::memory::prefetch;
 
@@ -18,13 +19,30 @@ pub struct ProbeResult {
 const CLUSTER_SIZE: usize = 4;
 const MIN_BUCKETS: usize = 1024;
 
-#[derive(Clone, Copy, Default)]
-#[repr(C)]
-struct QSEntry {
-    // 0 denotes empty
-    key: u32, // position hash with bit 0 toggled when in_check
-    value: i16,
-    bound: Bound,
+/// A single QS entry packed into one atomic word so it can be shared between
+/// search threads without tearing: [63:32]=key, [31:16]=value, [1:0]=bound.
+///
+/// The key is the position hash with bit 0 toggled when in check. 0 denotes empty.
+#[derive(Default)]
+struct QSEntry(AtomicU64);
+
+impl QSEntry {
+    #[inline]
+    fn load(&self) -> (u32, i16, Bound) {
+        let word = self.0.load(Ordering::Relaxed);
+        let bound = match word & 0x3 {
+            1 => Bound::Lower,
+            2 => Bound::Upper,
+            _ => Bound::Exact,
+        };
+        ((word >> 32) as u32, (word >> 16) as u16 as i16, bound)
+    }
+
+    #[inline]
+    fn set(&self, key: u32, value: i16, bound: Bound) {
+        let word = ((key as u64) << 32) | ((value as u16 as u64) << 16) | bound as u64;
+        self.0.store(word, Ordering::Relaxed);
+    }
 }
 
 pub struct QSTable {
@@ -41,17 +59,15 @@ impl QSTable {
         let buckets = (max_entries / CLUSTER_SIZE).max(MIN_BUCKETS);
         let total_entries = buckets * CLUSTER_SIZE;
 
-        Self {
-            entries: vec![QSEntry::default(); total_entries],
-            buckets,
-        }
+        let mut entries = Vec::with_capacity(total_entries);
+        entries.resize_with(total_entries, QSEntry::default);
+
+        Self { entries, buckets }
     }
 
-    pub fn clear(&mut self) {
-        unsafe {
-            let ptr = self.entries.as_mut_ptr() as *mut u8;
-            let size = self.entries.len() * size_of::<QSEntry>();
-            std::ptr::write_bytes(ptr, 0, size);
+    pub fn clear(&self) {
+        for entry in &self.entries {
+            entry.0.store(0, Ordering::Relaxed);
         }
     }
 
@@ -78,28 +94,27 @@ impl QSTable {
         let end = start + CLUSTER_SIZE;
         let cluster = &self.entries[start..end];
 
+        let loaded = [
+            cluster[0].load(),
+            cluster[1].load(),
+            cluster[2].load(),
+            cluster[3].load(),
+        ];
+
         // SIMD compare 4 keys at once
-        let keys = u32x4::from_array([
-            cluster[0].key,
-            cluster[1].key,
-            cluster[2].key,
-            cluster[3].key,
-        ]);
+        let keys = u32x4::from_array(loaded.map(|(key, ..)| key));
         let target = u32x4::splat(mixed);
         let mask = keys.simd_eq(target);
 
-        for (i, e) in cluster.iter().enumerate() {
+        for (i, &(_, value, bound)) in loaded.iter().enumerate() {
             if mask.test(i) {
-                return Some(ProbeResult {
-                    value: e.value,
-                    bound: e.bound,
-                });
+                return Some(ProbeResult { value, bound });
             }
         }
         None
     }
 
-    pub fn store(&mut self, hash: u64, value: i16, alpha: i16, beta: i16, in_check: bool) {
+    pub fn store(&self, hash: u64, value: i16, alpha: i16, beta: i16, in_check: bool) {
         let bound = if value <= alpha {
             Bound::Upper
         } else if value >= beta {
@@ -111,41 +126,30 @@ impl QSTable {
         let mixed = mix_key(hash, in_check);
         let start = self.cluster_start(mixed);
         let end = start + CLUSTER_SIZE;
-        let cluster = &mut self.entries[start..end];
+        let cluster = &self.entries[start..end];
 
         // Exact hit
-        for e in cluster.iter_mut() {
-            if e.key == mixed {
-                e.value = value;
-                e.bound = bound;
+        for e in cluster.iter() {
+            if e.load().0 == mixed {
+                e.set(mixed, value, bound);
                 return;
             }
         }
 
         // Empty slot
-        for e in cluster.iter_mut() {
-            if e.key == 0 {
-                e.key = mixed;
-                e.value = value;
-                e.bound = bound;
+        for e in cluster.iter() {
+            if e.load().0 == 0 {
+                e.set(mixed, value, bound);
                 return;
             }
         }
 
         // Prefer replacing a non-Exact bound; otherwise slot 0
-        if let Some((idx, _)) = cluster
+        let victim = cluster
             .iter()
-            .enumerate()
-            .find(|(_, e)| e.bound != Bound::Exact)
-        {
-            cluster[idx].key = mixed;
-            cluster[idx].value = value;
-            cluster[idx].bound = bound;
-        } else {
-            cluster[0].key = mixed;
-            cluster[0].value = value;
-            cluster[0].bound = bound;
-        }
+            .find(|e| e.load().2 != Bound::Exact)
+            .unwrap_or(&cluster[0]);
+        victim.set(mixed, value, bound);
     }
 }
 
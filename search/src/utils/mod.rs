@@ -1,4 +1,4 @@
 mod score;
 pub mod see;
 
-pub use score::{convert_centipawn_score, convert_mate_score};
+pub use score::{convert_centipawn_score, convert_mate_score, score_to_wdl};
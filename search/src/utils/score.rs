@@ -1,5 +1,9 @@
-use evaluation::scores::MATE_VALUE;
-use uci::commands::Score;
+use evaluation::{
+    scores::MATE_VALUE,
+    wdl::{Wdl, WDL_MODEL},
+};
+
+use crate::{SearchScore as Score, MAX_DEPTH};
 
 pub fn convert_mate_score(score: i16) -> Score {
     let mate_plies = (MATE_VALUE - score.abs()).max(0);
@@ -11,8 +15,30 @@ pub fn convert_mate_score(score: i16) -> Score {
     }
 }
 
-pub fn convert_centipawn_score(score: i16) -> Score {
-    Score::Centipawns(score)
+/// Reports a score normalized by the WDL model, so +100 means a 50% chance of winning.
+pub fn convert_centipawn_score(score: i16, material: i32) -> Score {
+    Score::Centipawns(WDL_MODEL.normalize_score(score, material))
+}
+
+/// Estimates win/draw/loss probabilities (per mille) for a score from the side to move's view.
+pub fn score_to_wdl(score: i16, material: i32) -> Wdl {
+    if score.abs() >= MATE_VALUE - MAX_DEPTH as i16 {
+        return if score > 0 {
+            Wdl {
+                win: 1000,
+                draw: 0,
+                loss: 0,
+            }
+        } else {
+            Wdl {
+                win: 0,
+                draw: 0,
+                loss: 1000,
+            }
+        };
+    }
+
+    WDL_MODEL.wdl(score, material)
 }
 
 #[cfg(test)]
@@ -51,15 +77,35 @@ mod tests {
         assert!(matches!(result, Score::Mate(2)));
     }
 
+    #[test]
+    fn test_score_to_wdl() {
+        let even = score_to_wdl(0, 58);
+        assert_eq!(even.win, even.loss);
+        assert_eq!(even.win + even.draw + even.loss, 1000);
+
+        let winning = score_to_wdl(400, 58);
+        assert!(winning.win > winning.loss);
+        assert_eq!(winning.win + winning.draw + winning.loss, 1000);
+
+        assert_eq!(score_to_wdl(-(MATE_VALUE - 1), 58).loss, 1000);
+    }
+
     #[test]
     fn test_convert_centipawn_score() {
+        let (a, _) = WDL_MODEL.params(58);
+        let even_odds = a.round() as i16;
+
         assert!(matches!(
-            convert_centipawn_score(100),
+            convert_centipawn_score(even_odds, 58),
             Score::Centipawns(100)
         ));
         assert!(matches!(
-            convert_centipawn_score(-50),
-            Score::Centipawns(-50)
+            convert_centipawn_score(-even_odds, 58),
+            Score::Centipawns(-100)
+        ));
+        assert!(matches!(
+            convert_centipawn_score(0, 58),
+            Score::Centipawns(0)
         ));
     }
 }
@@ -1,5 +1,5 @@
-use ahash::AHashSet;
 use cozy_chess::Board;
+use utils::GameHistory;
 
 /// Commands received from the UCI GUI.
 #[derive(Debug)]
@@ -11,9 +11,10 @@ pub enum UciInput {
     UciNewGame,
     Position {
         board: Board,
-        game_history: AHashSet<u64>,
+        game_history: GameHistory,
     },
     Go(GoParams),
+    PonderHit,
 
     Stop,
     Quit,
@@ -21,6 +22,22 @@ pub enum UciInput {
         name: String,
         value: String,
     },
+    /// Non-UCI: count move tree leaves with per-move output ("perft N" or "go perft N").
+    Perft {
+        depth: u8,
+    },
+    /// Non-UCI: print the position held by the engine ("d").
+    Display,
+    /// Non-UCI: show how the static evaluation of the current position is put together.
+    Eval,
+    /// Non-UCI: search the bench suite and report the node count ("bench [depth]").
+    Bench {
+        depth: Option<u8>,
+    },
+    /// A known command that could not be decoded.
+    Invalid {
+        reason: String,
+    },
     Unknown(String),
 }
 
@@ -31,25 +48,64 @@ pub enum UciOutput {
     IdAuthor(String),
     UciOk,
     ReadyOk,
-    BestMove(String),
+    BestMove {
+        best_move: String,
+        /// Expected reply to the best move, which the GUI may let us ponder on.
+        ponder: Option<String>,
+    },
     Info(Info),
+    /// The root move being searched, e.g. `info depth 12 currmove e2e4 currmovenumber 3`.
+    CurrMove {
+        depth: u8,
+        current_move: String,
+        /// 1-based index of the move in the root move list
+        number: u16,
+    },
     InfoString(String),
     Option(String),
+    /// Free-form, possibly multi-line output of a non-UCI debugging command.
+    Text(String),
+    /// Result of a perft command: leaf count per root move, then the total.
+    Perft {
+        divide: Vec<(String, u64)>,
+        nodes: u64,
+    },
 }
 
 /// Search information sent to the GUI during analysis.
 ///
-/// Example: `info depth 4 seldepth 7 nodes 3274 nps 922805 time 3 score cp 10 pv e2e4 d7d5 e4d5 d8d5`
+/// Example: `info depth 4 seldepth 7 multipv 1 nodes 3274 nps 922805 time 3 score cp 10 pv e2e4 d7d5 e4d5 d8d5`
 #[derive(Debug, Default, Clone)]
 pub struct Info {
     pub depth: u8,
     pub sel_depth: u8,
-    pub nodes: u32,
-    pub nodes_per_second: u32,
+    /// 1-based index of the line when searching multiple PVs. Omitted if not set.
+    pub multi_pv: Option<u16>,
+    pub nodes: u64,
+    pub nodes_per_second: u64,
     pub hashfull: u16,
     pub time: u32,
     pub pv: Vec<String>,
     pub score: Score,
+    /// Set when the score is only a bound, e.g. after an aspiration window failure.
+    pub bound: Option<ScoreBound>,
+    /// Win/draw/loss probabilities, sent when `UCI_ShowWDL` is enabled.
+    pub wdl: Option<Wdl>,
+}
+
+/// Marks a score as a lower (fail-high) or upper (fail-low) bound.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScoreBound {
+    Lower,
+    Upper,
+}
+
+/// Win/draw/loss probabilities in per mille, from the side to move's point of view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Wdl {
+    pub win: u16,
+    pub draw: u16,
+    pub loss: u16,
 }
 
 /// Evaluation score in UCI format.
@@ -84,8 +140,16 @@ pub struct GoParams {
     pub moves_to_go: Option<u64>,
     /// Search to this depth only.
     pub depth: Option<u8>,
+    /// Search this many nodes only.
+    pub nodes: Option<u64>,
+    /// Search for a mate in this many moves.
+    pub mate: Option<u8>,
     /// Search for exactly this many milliseconds.
     pub move_time: Option<u64>,
+    /// Restrict the search to these root moves (UCI notation). Empty means all moves.
+    pub search_moves: Vec<String>,
+    /// Search the predicted position in the opponent's time until "ponderhit" or "stop".
+    pub ponder: bool,
 }
 
 #[derive(Debug, Default)]
@@ -1,9 +1,27 @@
 use std::str::FromStr;
 
-use ahash::AHashSet;
 use cozy_chess::{util::parse_uci_move, Board};
+use utils::GameHistory;
 
 use super::commands::{GoParams, UciInput};
+use super::options::EMPTY_STRING;
+use super::utils::parse_fen;
+
+/// Tokens that start a new parameter in a "go" command.
+const GO_KEYWORDS: &[&str] = &[
+    "searchmoves",
+    "ponder",
+    "wtime",
+    "btime",
+    "winc",
+    "binc",
+    "movestogo",
+    "depth",
+    "nodes",
+    "mate",
+    "movetime",
+    "infinite",
+];
 
 pub struct Decoder;
 
@@ -18,74 +36,57 @@ impl Decoder {
         Self
     }
 
+    /// Decodes a line from the GUI. Never panics: malformed known commands
+    /// decode to [`UciInput::Invalid`] with a reason to report back.
     pub fn decode(&self, input: &str) -> UciInput {
-        match input {
+        let Some(command) = input.split_whitespace().next() else {
+            return UciInput::Unknown(input.to_string());
+        };
+
+        match command {
             "uci" => UciInput::Uci,
             "isready" => UciInput::IsReady,
             "ucinewgame" => UciInput::UciNewGame,
-
-            _ if input.starts_with("debug") => UciInput::Debug(input.ends_with(" on")),
-            _ if input.starts_with("position") => self.decode_position(input),
-            _ if input.starts_with("go") => self.decode_go(input),
-            _ if input.starts_with("setoption") => self.decode_setoption(input),
-            _ if input.starts_with("stop") => UciInput::Stop,
-            _ if input.starts_with("quit") => UciInput::Quit,
+            "ponderhit" => UciInput::PonderHit,
+            "stop" => UciInput::Stop,
+            "quit" => UciInput::Quit,
+            "d" => UciInput::Display,
+            "eval" => UciInput::Eval,
+
+            "debug" => self.decode_debug(input),
+            "position" => self.decode_position(input),
+            "go" => self.decode_go(input),
+            "setoption" => self.decode_setoption(input),
+            "bench" => self.decode_bench(input),
+            "perft" => self.decode_perft(input),
 
             _ => UciInput::Unknown(input.to_string()),
         }
     }
 
-    fn decode_position(&self, input: &str) -> UciInput {
-        let mut board = if input.contains("fen") {
-            // Extract FEN string (everything between "fen" and "moves" or end of string)
-            let fen = input
-                .split("fen")
-                .nth(1)
-                .unwrap()
-                .split("moves")
-                .next()
-                .unwrap()
-                .trim();
-            Board::from_str(fen).unwrap()
-        } else {
-            Board::default() // Default to startpos
-        };
-
-        // Track positions seen in the game (not including the current position)
-        // The current position will be the search root (included in search stack)
-        let mut game_history = AHashSet::new();
-
-        // Parse and apply moves
-        if input.contains("moves") {
-            let move_strings = input.split("moves").nth(1).unwrap().split_whitespace();
-
-            for mv_str in move_strings {
-                game_history.insert(board.hash());
-
-                if let Ok(mv) = parse_uci_move(&board, mv_str) {
-                    board.play(mv);
-                }
-            }
+    fn decode_debug(&self, input: &str) -> UciInput {
+        match input.split_whitespace().nth(1) {
+            Some("on") => UciInput::Debug(true),
+            Some("off") => UciInput::Debug(false),
+            _ => invalid(format!("debug: expected 'on' or 'off' in '{}'", input)),
         }
+    }
 
-        UciInput::Position {
-            board,
-            game_history,
+    fn decode_position(&self, input: &str) -> UciInput {
+        match parse_position(input) {
+            Ok((board, game_history)) => UciInput::Position {
+                board,
+                game_history,
+            },
+            Err(reason) => invalid(format!("position: {}", reason)),
         }
     }
 
     fn decode_setoption(&self, input: &str) -> UciInput {
         // Parse: setoption name <name> [value <value>]
         // Value is optional (button-type options have no value)
-        //
-        // TODO: Consider returning Result or adding InvalidCommand variant
-        // instead of using empty name to signal malformed commands.
         let Some(rest) = input.strip_prefix("setoption name ") else {
-            // Missing "name" keyword - return empty name for error handling
-            return UciInput::SetOption {
-                name: String::new(),
-                value: String::new(),
-            };
+            return invalid(format!("setoption: missing 'name' in '{}'", input));
         };
 
         let (name, value) = match rest.split_once(" value ") {
@@ -93,39 +94,163 @@ impl Decoder {
             None => (rest.trim(), ""),
         };
 
+        if name.is_empty() {
+            return invalid(format!("setoption: empty option name in '{}'", input));
+        }
+
+        // GUIs send "<empty>" to clear string options
+        let value = if value == EMPTY_STRING { "" } else { value };
+
         UciInput::SetOption {
             name: name.to_string(),
             value: value.to_string(),
         }
     }
 
+    fn decode_bench(&self, input: &str) -> UciInput {
+        match input.split_whitespace().nth(1).map(str::parse) {
+            None => UciInput::Bench { depth: None },
+            Some(Ok(depth)) => UciInput::Bench { depth: Some(depth) },
+            Some(Err(_)) => invalid(format!("bench: invalid depth in '{}'", input)),
+        }
+    }
+
+    fn decode_perft(&self, input: &str) -> UciInput {
+        let tokens: Vec<&str> = input.split_whitespace().collect();
+
+        match parse_param(&tokens, "perft") {
+            Ok(Some(depth)) => UciInput::Perft { depth },
+            Ok(None) => invalid(format!("perft: missing depth in '{}'", input)),
+            Err(reason) => invalid(format!("perft: {}", reason)),
+        }
+    }
+
     fn decode_go(&self, input: &str) -> UciInput {
-        UciInput::Go(GoParams {
-            infinite: input.contains("infinite"),
-            wtime: extract_numeric_param(input, "wtime"),
-            btime: extract_numeric_param(input, "btime"),
-            winc: extract_numeric_param(input, "winc"),
-            binc: extract_numeric_param(input, "binc"),
-            moves_to_go: extract_numeric_param(input, "movestogo"),
-
-            // TODO: Consider error handling
-            depth: extract_numeric_param(input, "depth").map(|d| d as u8),
-            move_time: extract_numeric_param(input, "movetime"),
-        })
+        if input.split_whitespace().nth(1) == Some("perft") {
+            return self.decode_perft(input);
+        }
+
+        match parse_go(input) {
+            Ok(params) => UciInput::Go(params),
+            Err(reason) => invalid(format!("go: {}", reason)),
+        }
+    }
+}
+
+fn invalid(reason: String) -> UciInput {
+    UciInput::Invalid { reason }
+}
+
+/// Parses "position [startpos | fen <fen>] [moves <move>...]".
+/// Fails on the first bad FEN or illegal move rather than searching a different position.
+fn parse_position(input: &str) -> Result<(Board, GameHistory), String> {
+    let rest = input
+        .trim()
+        .strip_prefix("position")
+        .unwrap_or_default()
+        .trim();
+
+    let (setup, moves) = match rest.split_once("moves") {
+        Some((setup, moves)) => (setup.trim(), moves),
+        None => (rest, ""),
+    };
+
+    let mut board = if setup == "startpos" {
+        Board::default()
+    } else if let Some(fen) = setup.strip_prefix("fen") {
+        parse_fen(fen.trim()).map_err(|e| format!("invalid fen '{}': {}", fen.trim(), e))?
+    } else {
+        return Err(format!("expected 'startpos' or 'fen', got '{}'", setup));
+    };
+
+    // Track positions seen in the game (not including the current position)
+    // The current position will be the search root (included in search stack)
+    let mut game_history = GameHistory::new();
+
+    for mv_str in moves.split_whitespace() {
+        let mv =
+            parse_uci_move(&board, mv_str).map_err(|_| format!("invalid move '{}'", mv_str))?;
+
+        if !board.is_legal(mv) {
+            return Err(format!("illegal move '{}'", mv_str));
+        }
+
+        game_history.play(&mut board, mv);
     }
+
+    Ok((board, game_history))
+}
+
+/// Parses "go" parameters. Values that are out of range are saturated: negative times
+/// (sent by some GUIs once the flag falls) count as 0 and limits too large for their
+/// type are capped. Missing or non-numeric values fail, rather than dropping a limit
+/// and searching forever.
+fn parse_go(input: &str) -> Result<GoParams, String> {
+    let tokens: Vec<&str> = input.split_whitespace().collect();
+
+    Ok(GoParams {
+        infinite: tokens.contains(&"infinite"),
+        wtime: parse_time(&tokens, "wtime")?,
+        btime: parse_time(&tokens, "btime")?,
+        winc: parse_time(&tokens, "winc")?,
+        binc: parse_time(&tokens, "binc")?,
+        moves_to_go: parse_count(&tokens, "movestogo", u64::MAX)?,
+        depth: parse_count(&tokens, "depth", u8::MAX)?,
+        nodes: parse_count(&tokens, "nodes", u64::MAX)?,
+        mate: parse_count(&tokens, "mate", u8::MAX)?,
+        move_time: parse_time(&tokens, "movetime")?,
+        search_moves: extract_search_moves(&tokens),
+        ponder: tokens.contains(&"ponder"),
+    })
+}
+
+/// Parses a time in milliseconds, clamping negative values to 0.
+fn parse_time(tokens: &[&str], param: &str) -> Result<Option<u64>, String> {
+    let time: Option<i128> = parse_param(tokens, param)?;
+    Ok(time.map(|time| time.clamp(0, u64::MAX as i128) as u64))
 }
 
-fn extract_numeric_param(input: &str, param: &str) -> Option<u64> {
-    input
-        .split_whitespace()
-        .collect::<Vec<&str>>()
-        .windows(2)
-        .find(|w| w[0] == param)
-        .and_then(|w| w[1].parse().ok())
+/// Parses a non-negative count, capping it at `max`.
+fn parse_count<T: TryFrom<u128>>(
+    tokens: &[&str],
+    param: &str,
+    max: T,
+) -> Result<Option<T>, String> {
+    let count: Option<u128> = parse_param(tokens, param)?;
+    Ok(count.map(|count| T::try_from(count).unwrap_or(max)))
+}
+
+/// Parses the value following `param`. A missing param is fine, a missing or bad value is not.
+fn parse_param<T: FromStr>(tokens: &[&str], param: &str) -> Result<Option<T>, String> {
+    let Some(index) = tokens.iter().position(|token| *token == param) else {
+        return Ok(None);
+    };
+
+    let value = tokens
+        .get(index + 1)
+        .ok_or_else(|| format!("missing value for '{}'", param))?;
+
+    value
+        .parse()
+        .map(Some)
+        .map_err(|_| format!("invalid value for '{}': '{}'", param, value))
+}
+
+/// Collects the moves following "searchmoves" up to the next go keyword.
+fn extract_search_moves(tokens: &[&str]) -> Vec<String> {
+    tokens
+        .iter()
+        .skip_while(|token| **token != "searchmoves")
+        .skip(1)
+        .take_while(|token| !GO_KEYWORDS.contains(token))
+        .map(|token| token.to_string())
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
+    use cozy_chess::Square;
+
     use super::*;
 
     #[test]
@@ -146,6 +271,42 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_bench() {
+        assert!(matches!(
+            Decoder.decode("bench"),
+            UciInput::Bench { depth: None }
+        ));
+        assert!(matches!(
+            Decoder.decode("bench 8"),
+            UciInput::Bench { depth: Some(8) }
+        ));
+        assert!(matches!(
+            Decoder.decode("bench deep"),
+            UciInput::Invalid { .. }
+        ));
+    }
+
+    #[test]
+    fn test_perft() {
+        assert!(matches!(
+            Decoder.decode("perft 5"),
+            UciInput::Perft { depth: 5 }
+        ));
+        assert!(matches!(
+            Decoder.decode("go perft 3"),
+            UciInput::Perft { depth: 3 }
+        ));
+        assert!(matches!(
+            Decoder.decode("go perft"),
+            UciInput::Invalid { .. }
+        ));
+        assert!(matches!(
+            Decoder.decode("perft x"),
+            UciInput::Invalid { .. }
+        ));
+    }
+
     #[test]
     fn test_setoption_with_value() {
         let UciInput::SetOption { name, value } = Decoder.decode("setoption name Hash value 256")
@@ -167,20 +328,29 @@ mod tests {
     }
 
     #[test]
-    fn test_setoption_malformed() {
-        // Missing "name" keyword - returns SetOption with empty name for error handling
-        let UciInput::SetOption { name, value } = Decoder.decode("setoption invalid") else {
+    fn test_setoption_empty_string() {
+        let UciInput::SetOption { value, .. } =
+            Decoder.decode("setoption name EvalFile value <empty>")
+        else {
             panic!("Expected SetOption")
         };
-        assert_eq!(name, "");
         assert_eq!(value, "");
+    }
 
-        // Also test other malformed variants
-        let UciInput::SetOption { name, value } = Decoder.decode("setoption value 123") else {
-            panic!("Expected SetOption")
-        };
-        assert_eq!(name, "");
-        assert_eq!(value, "");
+    #[test]
+    fn test_setoption_malformed() {
+        assert!(matches!(
+            Decoder.decode("setoption invalid"),
+            UciInput::Invalid { .. }
+        ));
+        assert!(matches!(
+            Decoder.decode("setoption value 123"),
+            UciInput::Invalid { .. }
+        ));
+        assert!(matches!(
+            Decoder.decode("setoption name  value 123"),
+            UciInput::Invalid { .. }
+        ));
     }
 
     #[test]
@@ -221,6 +391,36 @@ mod tests {
         assert_eq!(params.move_time, Some(5000));
     }
 
+    #[test]
+    fn test_go_nodes_and_mate() {
+        let UciInput::Go(params) = Decoder.decode("go nodes 100000 mate 3") else {
+            panic!("Expected Go")
+        };
+        assert_eq!(params.nodes, Some(100000));
+        assert_eq!(params.mate, Some(3));
+        assert!(params.search_moves.is_empty());
+    }
+
+    #[test]
+    fn test_go_searchmoves() {
+        let UciInput::Go(params) = Decoder.decode("go searchmoves e2e4 d2d4 depth 10") else {
+            panic!("Expected Go")
+        };
+        assert_eq!(params.search_moves, vec!["e2e4", "d2d4"]);
+        assert_eq!(params.depth, Some(10));
+    }
+
+    #[test]
+    fn test_go_ponder() {
+        let UciInput::Go(params) = Decoder.decode("go ponder wtime 60000 btime 60000") else {
+            panic!("Expected Go")
+        };
+        assert!(params.ponder);
+        assert_eq!(params.wtime, Some(60000));
+
+        assert!(matches!(Decoder.decode("ponderhit"), UciInput::PonderHit));
+    }
+
     #[test]
     fn test_position_startpos() {
         let UciInput::Position {
@@ -239,11 +439,12 @@ mod tests {
         let UciInput::Position {
             board,
             game_history,
-        } = Decoder.decode("position startpos moves e2e4 e7e5")
+        } = Decoder.decode("position startpos moves e2e4 e7e5 g1f3 b8c6")
         else {
             panic!("Expected Position")
         };
         assert_ne!(board, Board::default());
+        // Positions before the last pawn move can't repeat
         assert_eq!(game_history.len(), 2);
     }
 
@@ -261,6 +462,111 @@ mod tests {
         assert!(game_history.is_empty());
     }
 
+    #[test]
+    fn test_position_chess960_fen() {
+        let fen = "bqnbrkrn/pppppppp/8/8/8/8/PPPPPPPP/BQNBRKRN w GEge - 0 1";
+        let UciInput::Position { board, .. } =
+            Decoder.decode(&format!("position fen {} moves h1g3", fen))
+        else {
+            panic!("Expected Position")
+        };
+        assert_eq!(board.piece_on(Square::G3), Some(cozy_chess::Piece::Knight));
+    }
+
+    #[test]
+    fn test_go_saturates_out_of_range_values() {
+        let UciInput::Go(params) = Decoder.decode("go wtime -5 btime 1000 winc -1") else {
+            panic!("Expected Go")
+        };
+        assert_eq!(params.wtime, Some(0));
+        assert_eq!(params.btime, Some(1000));
+        assert_eq!(params.winc, Some(0));
+
+        let UciInput::Go(params) = Decoder.decode("go depth 300 nodes 99999999999999999999999")
+        else {
+            panic!("Expected Go")
+        };
+        assert_eq!(params.depth, Some(u8::MAX));
+        assert_eq!(params.nodes, Some(u64::MAX));
+    }
+
+    /// Malformed input that must decode to `Invalid` rather than panic or be silently accepted.
+    const MALFORMED_CORPUS: &[&str] = &[
+        "debug",
+        "debug maybe",
+        "position",
+        "position random",
+        "position fen",
+        "position fen not-a-fen",
+        "position fen 8/8/8/8/8/8/8/8 w - - 0 1",
+        "position fen rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR x KQkq - 0 1",
+        "position fen rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq e9 0 1",
+        "position fen rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 extra",
+        "position startpos moves e2e5",
+        "position startpos moves e7e5",
+        "position startpos moves e2e4 zzzz",
+        "position startpos moves e2e4 e2e4",
+        "setoption",
+        "setoption name",
+        "setoption value 1",
+        "go depth",
+        "go depth abc",
+        "go depth -1",
+        "go nodes 1e6",
+        "go mate",
+        "go wtime abc",
+    ];
+
+    #[test]
+    fn test_malformed_corpus() {
+        for input in MALFORMED_CORPUS {
+            assert!(
+                matches!(Decoder.decode(input), UciInput::Invalid { .. }),
+                "Expected Invalid for '{}'",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_fuzz_does_not_panic() {
+        const SEEDS: &[&str] = &[
+            "position startpos moves e2e4 e7e5 g1f3 b8c6",
+            "position fen r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1 moves e1g1",
+            "position fen bqnbrkrn/pppppppp/8/8/8/8/PPPPPPPP/BQNBRKRN w GEge - 0 1",
+            "go wtime 1000 btime 1000 winc 10 binc 10 searchmoves e2e4 depth 5",
+            "setoption name Hash value 64",
+            "debug on",
+        ];
+        const ALPHABET: &[u8] = b" 0123456789/-abcdefghkmnpqrwKQRBNP";
+
+        // Deterministic xorshift so failures are reproducible
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as usize
+        };
+
+        for seed in SEEDS {
+            // Every truncation of a valid command
+            for end in 0..=seed.len() {
+                Decoder.decode(&seed[..end]);
+            }
+
+            // Random single and double character mutations
+            for _ in 0..1000 {
+                let mut bytes = seed.as_bytes().to_vec();
+                for _ in 0..1 + next() % 2 {
+                    let index = next() % bytes.len();
+                    bytes[index] = ALPHABET[next() % ALPHABET.len()];
+                }
+                Decoder.decode(std::str::from_utf8(&bytes).unwrap());
+            }
+        }
+    }
+
     #[test]
     fn test_unknown_command() {
         assert!(matches!(
@@ -1,4 +1,4 @@
-use super::commands::{Score, UciOutput};
+use super::commands::{Score, ScoreBound, UciOutput};
 
 pub struct Encoder {}
 
@@ -11,16 +11,32 @@ impl Encoder {
             UciOutput::UciOk => "uciok".to_string(),
             UciOutput::ReadyOk => "readyok".to_string(),
 
-            UciOutput::BestMove(best_move) => format!("bestmove {}", best_move),
+            UciOutput::BestMove { best_move, ponder } => match ponder {
+                Some(ponder) => format!("bestmove {} ponder {}", best_move, ponder),
+                None => format!("bestmove {}", best_move),
+            },
             UciOutput::Info(info) => {
                 format!(
-                    "info depth {} seldepth {} multipv 1 score {} nodes {} nps {} hashfull {} time {} pv {}",
+                    "info depth {} seldepth {}{} score {}{}{} nodes {} nps {} hashfull {} time {} pv {}",
                     info.depth,
                     info.sel_depth,
+                    match info.multi_pv {
+                        Some(index) => format!(" multipv {}", index),
+                        None => String::new(),
+                    },
                     match info.score {
                         Score::Centipawns(cp) => format!("cp {}", cp),
                         Score::Mate(moves) => format!("mate {}", moves),
                     },
+                    match info.bound {
+                        Some(ScoreBound::Lower) => " lowerbound",
+                        Some(ScoreBound::Upper) => " upperbound",
+                        None => "",
+                    },
+                    match info.wdl {
+                        Some(wdl) => format!(" wdl {} {} {}", wdl.win, wdl.draw, wdl.loss),
+                        None => String::new(),
+                    },
                     info.nodes,
                     info.nodes_per_second,
                     info.hashfull,
@@ -28,8 +44,82 @@ impl Encoder {
                     info.pv.join(" ")
                 )
             }
+            UciOutput::CurrMove {
+                depth,
+                current_move,
+                number,
+            } => format!(
+                "info depth {} currmove {} currmovenumber {}",
+                depth, current_move, number
+            ),
             UciOutput::Option(option_str) => option_str.clone(),
             UciOutput::InfoString(message) => format!("info string {}", message),
+            UciOutput::Text(text) => text.clone(),
+            UciOutput::Perft { divide, nodes } => {
+                let mut lines: Vec<String> = divide
+                    .iter()
+                    .map(|(mv, count)| format!("{}: {}", mv, count))
+                    .collect();
+                lines.push(String::new());
+                lines.push(format!("Nodes searched: {}", nodes));
+                lines.join("\n")
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{Info, Wdl};
+
+    #[test]
+    fn test_encode_info_with_bound_and_wdl() {
+        let info = Info {
+            depth: 10,
+            sel_depth: 14,
+            nodes: 5_000_000_000,
+            score: Score::Centipawns(35),
+            bound: Some(ScoreBound::Lower),
+            wdl: Some(Wdl {
+                win: 250,
+                draw: 700,
+                loss: 50,
+            }),
+            pv: vec!["e2e4".to_string()],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            Encoder {}.encode(&UciOutput::Info(info)),
+            "info depth 10 seldepth 14 score cp 35 lowerbound wdl 250 700 50 nodes 5000000000 nps 0 hashfull 0 time 0 pv e2e4"
+        );
+    }
+
+    #[test]
+    fn test_encode_currmove() {
+        let output = UciOutput::CurrMove {
+            depth: 12,
+            current_move: "g1f3".to_string(),
+            number: 3,
+        };
+
+        assert_eq!(
+            Encoder {}.encode(&output),
+            "info depth 12 currmove g1f3 currmovenumber 3"
+        );
+    }
+
+    #[test]
+    fn test_encode_perft() {
+        let output = UciOutput::Perft {
+            divide: vec![("e2e4".to_string(), 20), ("d2d4".to_string(), 20)],
+            nodes: 40,
+        };
+
+        assert_eq!(
+            Encoder {}.encode(&output),
+            "e2e4: 20\nd2d4: 20\n\nNodes searched: 40"
+        );
+    }
+}
@@ -10,8 +10,8 @@ pub use commands::{UciInput, UciOutput};
 pub use connection::UciConnection;
 pub use decoder::Decoder;
 pub use encoder::Encoder;
-pub use options::{UciOption, UciOptionType};
-pub use utils::{move_to_uci, pv_to_uci};
+pub use options::{UciOptionType, EMPTY_STRING};
+pub use utils::{move_to_uci, parse_fen, pv_to_uci};
 
 /// Null move in UCI format, used when no legal move exists (e.g., checkmate).
 /// Per UCI spec, this should be sent as the bestmove when the position has no legal moves.
@@ -1,34 +1,25 @@
-#[derive(Debug, Clone)]
-pub struct UciOption {
-    pub name: &'static str,
-    pub option_type: UciOptionType,
-}
+/// UCI spelling of an empty string option value.
+pub const EMPTY_STRING: &str = "<empty>";
 
+/// Option types as announced to the GUI in `option` lines.
 #[derive(Debug, Clone)]
 pub enum UciOptionType {
-    Spin { min: i32, max: i32 },
+    Spin {
+        min: i32,
+        max: i32,
+    },
     Check,
+    /// An action without a value (e.g. "Clear Hash").
+    Button,
+    /// Free text, such as a file path.
+    String,
+    /// One of a fixed set of values.
+    Combo {
+        options: &'static [&'static str],
+    },
 }
 
 impl UciOptionType {
-    pub fn validate(&self, value: &str) -> Result<(), String> {
-        match self {
-            UciOptionType::Spin { min, max } => {
-                let parsed = value
-                    .parse::<i32>()
-                    .map_err(|e| format!("Invalid integer: {}", e))?;
-                if parsed < *min || parsed > *max {
-                    return Err(format!("Value {} out of range [{}, {}]", parsed, min, max));
-                }
-                Ok(())
-            }
-            UciOptionType::Check => match value.to_lowercase().as_str() {
-                "true" | "false" => Ok(()),
-                _ => Err("Boolean value must be 'true' or 'false'".to_string()),
-            },
-        }
-    }
-
     pub fn to_uci<T>(&self, name: &str, current_value: &T) -> String
     where
         T: ToString,
@@ -50,6 +41,60 @@ impl UciOptionType {
                     current_value.to_string()
                 )
             }
+            UciOptionType::Button => format!("option name {} type button", name),
+            UciOptionType::String => {
+                let value = current_value.to_string();
+                format!(
+                    "option name {} type string default {}",
+                    name,
+                    if value.is_empty() {
+                        EMPTY_STRING
+                    } else {
+                        &value
+                    }
+                )
+            }
+            UciOptionType::Combo { options } => {
+                let vars: String = options.iter().map(|var| format!(" var {}", var)).collect();
+                format!(
+                    "option name {} type combo default {}{}",
+                    name,
+                    current_value.to_string(),
+                    vars
+                )
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_button() {
+        assert_eq!(
+            UciOptionType::Button.to_uci("Clear Hash", &""),
+            "option name Clear Hash type button"
+        );
+    }
+
+    #[test]
+    fn test_string() {
+        assert_eq!(
+            UciOptionType::String.to_uci("EvalFile", &""),
+            "option name EvalFile type string default <empty>"
+        );
+    }
+
+    #[test]
+    fn test_combo() {
+        let combo = UciOptionType::Combo {
+            options: &["Auto", "HCE", "NNUE"],
+        };
+        assert_eq!(
+            combo.to_uci("Eval", &"Auto"),
+            "option name Eval type combo default Auto var Auto var HCE var NNUE"
+        );
+    }
+}
@@ -1,21 +1,123 @@
 // cozy-chess uses "king captures rook" notation for castling internally (e.g., e1h1),
 // but UCI expects standard notation (e.g., e1g1). These utils handle some common conversions.
+//
+// In Chess960 mode (UCI_Chess960), UCI uses the same king-captures-rook notation as cozy-chess.
+// Parsing needs no mode: `parse_uci_move` only rewrites e1g1/e1c1-style moves, which can never
+// be a regular king move, and leaves king-captures-rook moves as they are.
 
-use cozy_chess::{util::display_uci_move, Board, Move};
+use cozy_chess::{
+    util::display_uci_move, BitBoard, Board, Color, FenParseError, Move, Piece, Square,
+};
 
 #[inline]
-pub fn move_to_uci(board: &Board, mv: Move) -> String {
-    display_uci_move(board, mv).to_string()
+pub fn move_to_uci(board: &Board, mv: Move, chess960: bool) -> String {
+    if chess960 {
+        mv.to_string()
+    } else {
+        display_uci_move(board, mv).to_string()
+    }
 }
 
-pub fn pv_to_uci(starting_board: &Board, pv: &[Move]) -> Vec<String> {
+pub fn pv_to_uci(starting_board: &Board, pv: &[Move], chess960: bool) -> Vec<String> {
     let mut result = Vec::with_capacity(pv.len());
     let mut board = starting_board.clone();
 
     for &mv in pv {
-        result.push(move_to_uci(&board, mv));
+        result.push(move_to_uci(&board, mv, chess960));
         board.play_unchecked(mv);
     }
 
     result
 }
+
+/// Parses a FEN in standard, Shredder-FEN (`HAha`) or X-FEN (`KQkq` meaning the outermost rook) notation.
+/// Missing move counters (EPD-style) default to `0 1`.
+pub fn parse_fen(fen: &str) -> Result<Board, FenParseError> {
+    let mut fields: Vec<&str> = fen.split_whitespace().collect();
+    if fields.len() == 4 {
+        fields.extend(["0", "1"]);
+    }
+
+    let standard_error = match Board::from_fen(&fields.join(" "), false) {
+        Ok(board) => return Ok(board),
+        Err(error) => error,
+    };
+
+    if fields.len() < 3 {
+        return Err(standard_error);
+    }
+
+    // Parse without castling rights to find the kings and rooks
+    let castling = fields[2];
+    fields[2] = "-";
+    let board = Board::from_fen(&fields.join(" "), false)?;
+
+    let shredder_castling = castling
+        .chars()
+        .map(|right| shredder_castle_right(&board, right))
+        .collect::<Option<String>>()
+        .ok_or(FenParseError::InvalidCastlingRights)?;
+
+    fields[2] = &shredder_castling;
+    Board::from_fen(&fields.join(" "), true)
+}
+
+/// Converts an X-FEN castling right (`K`/`Q`, or a rook file) to its Shredder-FEN rook file.
+fn shredder_castle_right(board: &Board, right: char) -> Option<char> {
+    let color = if right.is_ascii_uppercase() {
+        Color::White
+    } else {
+        Color::Black
+    };
+
+    let short = match right.to_ascii_lowercase() {
+        'k' => true,
+        'q' => false,
+        '-' => return Some(right),
+        file if ('a'..='h').contains(&file) => return Some(right),
+        _ => return None,
+    };
+
+    let king = board.king(color);
+    let back_rank_rooks: BitBoard =
+        board.colored_pieces(color, Piece::Rook) & king.rank().bitboard();
+
+    let mut rooks = back_rank_rooks
+        .into_iter()
+        .filter(|rook: &Square| (rook.file() > king.file()) == short);
+    let rook = if short { rooks.last() } else { rooks.next() }?;
+
+    let file = char::from(rook.file());
+    Some(if color == Color::White {
+        file.to_ascii_uppercase()
+    } else {
+        file
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use cozy_chess::File;
+
+    use super::*;
+
+    #[test]
+    fn test_castling_encoding() {
+        let board = parse_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+        let short: Move = "e1h1".parse().unwrap();
+
+        assert_eq!(move_to_uci(&board, short, false), "e1g1");
+        assert_eq!(move_to_uci(&board, short, true), "e1h1");
+    }
+
+    #[test]
+    fn test_parse_fen_chess960() {
+        let shredder = "bqnbrkrn/pppppppp/8/8/8/8/PPPPPPPP/BQNBRKRN w GEge - 0 1";
+        let x_fen = "bqnbrkrn/pppppppp/8/8/8/8/PPPPPPPP/BQNBRKRN w KQkq - 0 1";
+
+        let board = parse_fen(shredder).unwrap();
+        assert_eq!(board.castle_rights(Color::White).short, Some(File::G));
+        assert_eq!(board.castle_rights(Color::White).long, Some(File::E));
+        assert_eq!(parse_fen(x_fen).unwrap(), board);
+    }
+}
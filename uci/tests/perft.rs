@@ -0,0 +1,96 @@
+//! Move generation regression suite: perft counts of published positions, and
+//! UCI notation round trips of every legal move in them.
+//!
+//! The suites use Ethereal's EPD format, `<fen> ;D1 <nodes> ;D2 <nodes> ...`:
+//! - `standard.epd`: the classic `perftsuite.epd` plus the Chess Programming Wiki
+//!   positions 2-6 <https://www.chessprogramming.org/Perft_Results>
+//! - `chess960.epd`: positions from the published Chess960 perft results
+//!   <https://www.chessprogramming.org/Chess960_Perft_Results>
+
+use cozy_chess::util::parse_uci_move;
+use uci::{move_to_uci, parse_fen};
+use utils::{collect_legal_moves, make_move, perft};
+
+/// Deeper entries are skipped to keep the suite fast in debug builds.
+const MAX_TEST_NODES: u64 = 200_000;
+
+/// Name, contents, and whether castling is written in Chess960 notation.
+const SUITES: &[(&str, &str, bool)] = &[
+    ("standard", include_str!("perft/standard.epd"), false),
+    ("chess960", include_str!("perft/chess960.epd"), true),
+];
+
+fn parse_epd_line(line: &str) -> (&str, Vec<(u8, u64)>) {
+    let mut fields = line.split(';');
+    let fen = fields.next().unwrap().trim();
+
+    let counts = fields
+        .map(|field| {
+            let (depth, nodes) = field.trim().split_once(' ').unwrap();
+            let depth = depth.trim_start_matches('D').parse().unwrap();
+            (depth, nodes.parse().unwrap())
+        })
+        .collect();
+
+    (fen, counts)
+}
+
+fn suite_lines(epd: &str) -> impl Iterator<Item = &str> {
+    epd.lines().filter(|line| !line.trim().is_empty())
+}
+
+#[test]
+fn test_perft_suites() {
+    for (suite, epd, _) in SUITES {
+        for line in suite_lines(epd) {
+            let (fen, counts) = parse_epd_line(line);
+            let board = parse_fen(fen).unwrap();
+
+            for (depth, expected) in counts {
+                if expected > MAX_TEST_NODES {
+                    continue;
+                }
+                assert_eq!(
+                    perft(&board, depth),
+                    expected,
+                    "{} suite: perft({}) of {}",
+                    suite,
+                    depth,
+                    fen
+                );
+            }
+        }
+    }
+}
+
+/// Every legal move must survive a round trip through its UCI notation,
+/// including castling in both standard and Chess960 notation.
+#[test]
+fn test_uci_move_round_trip() {
+    for (suite, epd, chess960) in SUITES {
+        for line in suite_lines(epd) {
+            let (fen, _) = parse_epd_line(line);
+            let root = parse_fen(fen).unwrap();
+
+            let boards = std::iter::once(root.clone()).chain(
+                collect_legal_moves(&root)
+                    .into_iter()
+                    .map(|mv| make_move(&root, mv)),
+            );
+
+            for board in boards {
+                for mv in collect_legal_moves(&board) {
+                    let notation = move_to_uci(&board, mv, *chess960);
+                    assert_eq!(
+                        parse_uci_move(&board, &notation).ok(),
+                        Some(mv),
+                        "{} suite: {} in {}",
+                        suite,
+                        notation,
+                        board
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use cozy_chess::{Board, Move};
+
+use crate::{has_check, has_legal_moves};
+
+/// Plies without a capture or pawn move after which the game is drawn.
+pub const FIFTY_MOVE_PLIES: u8 = 100;
+
+/// Fifty-move rule: drawn after 100 plies without a capture or pawn move,
+/// unless the last move delivered checkmate.
+pub fn is_fifty_move_draw(board: &Board) -> bool {
+    board.halfmove_clock() >= FIFTY_MOVE_PLIES && (!has_check(board) || has_legal_moves(board))
+}
+
+/// Positions played before the current one, oldest first, for repetition detection.
+///
+/// Only positions since the last irreversible move (capture or pawn move) are kept,
+/// since earlier ones can never occur again.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GameHistory {
+    hashes: Vec<u64>,
+}
+
+impl GameHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Plays `mv` on `board`, recording the position it leaves.
+    pub fn play(&mut self, board: &mut Board, mv: Move) {
+        let hash = board.hash();
+        board.play_unchecked(mv);
+
+        if board.halfmove_clock() == 0 {
+            self.hashes.clear();
+        } else {
+            self.hashes.push(hash);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.hashes.clear();
+    }
+
+    /// Hash of the position `plies` half-moves before the current one (1 is the previous position).
+    pub fn plies_ago(&self, plies: usize) -> Option<u64> {
+        plies
+            .checked_sub(1)
+            .and_then(|index| self.hashes.iter().rev().nth(index))
+            .copied()
+    }
+
+    /// How many times the position with this hash has occurred.
+    pub fn count(&self, hash: u64) -> usize {
+        self.hashes.iter().filter(|&&h| h == hash).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cozy_chess::util::parse_uci_move;
+
+    fn play_all(board: &mut Board, history: &mut GameHistory, moves: &str) {
+        for mv in moves.split_whitespace() {
+            let mv = parse_uci_move(board, mv).unwrap();
+            history.play(board, mv);
+        }
+    }
+
+    #[test]
+    fn test_counts_repetitions() {
+        let mut board = Board::default();
+        let mut history = GameHistory::new();
+        let start = board.hash();
+
+        play_all(
+            &mut board,
+            &mut history,
+            "g1f3 g8f6 f3g1 f6g8 g1f3 g8f6 f3g1 f6g8",
+        );
+
+        assert_eq!(board.hash(), start);
+        assert_eq!(history.len(), 8);
+        assert_eq!(history.count(start), 2);
+        assert_eq!(history.plies_ago(8), Some(start));
+        assert_eq!(history.plies_ago(9), None);
+    }
+
+    #[test]
+    fn test_irreversible_move_clears_history() {
+        let mut board = Board::default();
+        let mut history = GameHistory::new();
+
+        play_all(&mut board, &mut history, "g1f3 g8f6 e2e4");
+        assert!(history.is_empty());
+
+        play_all(&mut board, &mut history, "b8c6");
+        assert_eq!(history.len(), 1);
+    }
+
+    #[test]
+    fn test_fifty_move_draw() {
+        let board: Board = "7k/8/6K1/8/8/8/8/8 w - - 99 80".parse().unwrap();
+        assert!(!is_fifty_move_draw(&board));
+
+        let board: Board = "7k/8/6K1/8/8/8/8/8 b - - 100 80".parse().unwrap();
+        assert!(is_fifty_move_draw(&board));
+
+        // Checkmate on the 100th ply still wins
+        let board: Board = "R6k/8/6K1/8/8/8/8/8 b - - 100 80".parse().unwrap();
+        assert!(!is_fifty_move_draw(&board));
+    }
+}
@@ -5,13 +5,16 @@ mod attacks;
 pub mod bitset;
 pub mod board_metrics;
 mod eval;
+mod history;
 mod material;
 pub mod memory;
 mod moves;
+mod perft;
 mod position;
 
 pub use attacks::{get_attackers_to, get_discovered_attacks};
 pub use eval::flip_eval_perspective;
+pub use history::{is_fifty_move_draw, GameHistory, FIFTY_MOVE_PLIES};
 pub use material::{
     cap_eval_by_material, game_phase, has_insufficient_material, is_zugzwang, majors, minors,
     side_has_insufficient_material,
@@ -19,4 +22,5 @@ pub use material::{
 pub use moves::{
     collect_legal_moves, gives_check, has_check, has_legal_moves, is_capture, make_move, only_move,
 };
+pub use perft::{perft, perft_divide};
 pub use position::Position;
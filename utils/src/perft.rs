@@ -0,0 +1,47 @@
+use cozy_chess::{Board, Move};
+
+use crate::{collect_legal_moves, make_move};
+
+/// Counts the leaf nodes of the legal move tree to `depth`.
+///
+/// Used to verify move generation against known node counts.
+/// <https://www.chessprogramming.org/Perft>
+pub fn perft(board: &Board, depth: u8) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+
+    let moves = collect_legal_moves(board);
+
+    // Bulk counting: the moves at the last ply are the leaves
+    if depth == 1 {
+        return moves.len() as u64;
+    }
+
+    moves
+        .into_iter()
+        .map(|mv| perft(&make_move(board, mv), depth - 1))
+        .sum()
+}
+
+/// Perft split by root move ("divide"), to narrow down move generation bugs.
+pub fn perft_divide(board: &Board, depth: u8) -> Vec<(Move, u64)> {
+    collect_legal_moves(board)
+        .into_iter()
+        .map(|mv| (mv, perft(&make_move(board, mv), depth.saturating_sub(1))))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_divide_sums_to_perft() {
+        let board = Board::default();
+        let divide = perft_divide(&board, 3);
+
+        assert_eq!(divide.len(), 20);
+        assert_eq!(divide.iter().map(|(_, nodes)| nodes).sum::<u64>(), 8902);
+    }
+}
@@ -0,0 +1,81 @@
+use cozy_chess::Board;
+
+/// Commands received from an XBoard/CECP (protocol version 2) GUI.
+///
+/// Times are converted to milliseconds.
+#[derive(Debug)]
+pub enum XBoardInput {
+    XBoard,
+    ProtoVer(u32),
+    New,
+    /// Stop playing either side, just update the board with incoming moves.
+    Force,
+    /// Play the side to move and start thinking.
+    Go,
+    /// The opponent's move in coordinate notation (e.g. `e2e4`, `e7e8q`).
+    UserMove(String),
+    /// Move now ("?").
+    MoveNow,
+    /// Conventional clock: `moves_per_control` moves (0 for the whole game) in `base`, plus `increment` per move.
+    Level {
+        moves_per_control: u64,
+        base: u64,
+        increment: u64,
+    },
+    /// Exact time per move ("st").
+    MoveTime(u64),
+    /// Depth limit ("sd").
+    Depth(u8),
+    /// Time left on the engine's clock ("time").
+    Time(u64),
+    /// Time left on the opponent's clock ("otim").
+    OTime(u64),
+    SetBoard(Board),
+    Analyze,
+    /// Leave analyze mode.
+    Exit,
+    Post,
+    NoPost,
+    /// The game ended, e.g. `result 1-0 {White mates}`.
+    Result(String),
+    Ping(u32),
+    Quit,
+    /// A command that needs no reply from us (e.g. "random", "hard", "accepted").
+    Ignored,
+    /// A known command that could not be decoded.
+    Invalid {
+        reason: String,
+        command: String,
+    },
+    Unknown(String),
+}
+
+/// Commands sent to the XBoard GUI.
+#[derive(Debug)]
+pub enum XBoardOutput {
+    /// Supported protocol features, e.g. `feature ping=1 myname="Grail"`.
+    Features(Vec<(String, String)>),
+    /// Our move, e.g. `move e2e4`.
+    Move(String),
+    /// Thinking output: `depth score time nodes pv`, with the score in centipawns
+    /// and the time in centiseconds.
+    Thinking {
+        depth: u8,
+        score: i32,
+        time: u32,
+        nodes: u64,
+        pv: Vec<String>,
+    },
+    Pong(u32),
+    IllegalMove {
+        mv: String,
+        reason: String,
+    },
+    /// `Error (kind): command`
+    Error {
+        kind: String,
+        command: String,
+    },
+    /// Diagnostic text, ignored by the GUI.
+    Comment(String),
+}
@@ -0,0 +1,40 @@
+use super::commands::XBoardOutput;
+use super::encoder::Encoder;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread;
+
+/// Handles XBoard output communication.
+///
+/// Spawns a dedicated thread for printing responses to stdout, like `UciConnection`.
+pub struct XBoardConnection {
+    output_tx: Sender<XBoardOutput>,
+}
+
+impl Default for XBoardConnection {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl XBoardConnection {
+    pub fn new() -> Self {
+        let (output_tx, output_rx) = channel();
+        Self::spawn_output_handler(output_rx);
+        Self { output_tx }
+    }
+
+    /// Returns a sender for XBoard output messages.
+    pub fn output_sender(&self) -> Sender<XBoardOutput> {
+        self.output_tx.clone()
+    }
+
+    fn spawn_output_handler(output_rx: Receiver<XBoardOutput>) {
+        thread::spawn(move || {
+            let encoder = Encoder {};
+
+            while let Ok(output) = output_rx.recv() {
+                println!("{}", encoder.encode(&output));
+            }
+        });
+    }
+}
@@ -0,0 +1,231 @@
+use std::str::FromStr;
+
+use uci::parse_fen;
+
+use super::commands::XBoardInput;
+
+/// Commands that need no reply from us.
+const IGNORED_COMMANDS: &[&str] = &[
+    "accepted", "rejected", "random", "hard", "easy", "computer", "name", "rating", "ics", "draw",
+    "hint", "bk", ".",
+];
+
+pub struct Decoder;
+
+impl Default for Decoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Decoder {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Decodes a line from the GUI. Never panics: malformed known commands
+    /// decode to [`XBoardInput::Invalid`] with a reason to report back.
+    pub fn decode(&self, input: &str) -> XBoardInput {
+        let Some(command) = input.split_whitespace().next() else {
+            return XBoardInput::Unknown(input.to_string());
+        };
+
+        match command {
+            "xboard" => XBoardInput::XBoard,
+            "new" => XBoardInput::New,
+            "force" => XBoardInput::Force,
+            "go" => XBoardInput::Go,
+            "?" => XBoardInput::MoveNow,
+            "analyze" => XBoardInput::Analyze,
+            "exit" => XBoardInput::Exit,
+            "post" => XBoardInput::Post,
+            "nopost" => XBoardInput::NoPost,
+            "quit" => XBoardInput::Quit,
+            "result" => XBoardInput::Result(argument(input).to_string()),
+
+            "protover" => decode_number(input, XBoardInput::ProtoVer),
+            "ping" => decode_number(input, XBoardInput::Ping),
+            "sd" => decode_number(input, XBoardInput::Depth),
+            "st" => decode_number(input, |seconds: u64| XBoardInput::MoveTime(seconds * 1000)),
+            "time" => decode_number(input, |centis: u64| XBoardInput::Time(centis * 10)),
+            "otim" => decode_number(input, |centis: u64| XBoardInput::OTime(centis * 10)),
+            "level" => self.decode_level(input),
+            "setboard" => self.decode_setboard(input),
+            "usermove" => match input.split_whitespace().nth(1) {
+                Some(mv) => XBoardInput::UserMove(mv.to_string()),
+                None => invalid(input, "missing move"),
+            },
+
+            _ if IGNORED_COMMANDS.contains(&command) => XBoardInput::Ignored,
+            // Protocol version 1 GUIs send moves without "usermove"
+            _ if is_coordinate_move(command) => XBoardInput::UserMove(command.to_string()),
+            _ => XBoardInput::Unknown(input.to_string()),
+        }
+    }
+
+    /// Parses "level MPS BASE INC", where BASE is minutes or "minutes:seconds"
+    /// and INC is seconds (possibly fractional).
+    fn decode_level(&self, input: &str) -> XBoardInput {
+        let tokens: Vec<&str> = input.split_whitespace().collect();
+        let [_, moves_per_control, base, increment] = tokens[..] else {
+            return invalid(input, "expected 'level MPS BASE INC'");
+        };
+
+        let Ok(moves_per_control) = moves_per_control.parse() else {
+            return invalid(input, "invalid moves per control");
+        };
+
+        let base = match base.split_once(':') {
+            Some((minutes, seconds)) => minutes
+                .parse::<u64>()
+                .ok()
+                .zip(seconds.parse::<u64>().ok())
+                .map(|(minutes, seconds)| (minutes * 60 + seconds) * 1000),
+            None => base.parse::<u64>().ok().map(|minutes| minutes * 60_000),
+        };
+        let Some(base) = base else {
+            return invalid(input, "invalid base time");
+        };
+
+        let increment = match increment.parse::<f64>() {
+            Ok(seconds) if seconds >= 0.0 => (seconds * 1000.0).round() as u64,
+            _ => return invalid(input, "invalid increment"),
+        };
+
+        XBoardInput::Level {
+            moves_per_control,
+            base,
+            increment,
+        }
+    }
+
+    fn decode_setboard(&self, input: &str) -> XBoardInput {
+        match parse_fen(argument(input)) {
+            Ok(board) => XBoardInput::SetBoard(board),
+            Err(_) => invalid(input, "invalid FEN"),
+        }
+    }
+}
+
+/// Everything after the command word.
+fn argument(input: &str) -> &str {
+    input
+        .trim()
+        .split_once(char::is_whitespace)
+        .map_or("", |(_, rest)| rest.trim())
+}
+
+/// Decodes a command with a single numeric argument.
+fn decode_number<T: FromStr>(input: &str, make: impl FnOnce(T) -> XBoardInput) -> XBoardInput {
+    match input.split_whitespace().nth(1).map(str::parse) {
+        Some(Ok(value)) => make(value),
+        _ => invalid(input, "expected a number"),
+    }
+}
+
+fn invalid(command: &str, reason: &str) -> XBoardInput {
+    XBoardInput::Invalid {
+        reason: reason.to_string(),
+        command: command.to_string(),
+    }
+}
+
+/// Matches moves like `e2e4` and `e7e8q`.
+fn is_coordinate_move(token: &str) -> bool {
+    let bytes = token.as_bytes();
+    let square =
+        |file: u8, rank: u8| (b'a'..=b'h').contains(&file) && (b'1'..=b'8').contains(&rank);
+
+    match bytes.len() {
+        4 => square(bytes[0], bytes[1]) && square(bytes[2], bytes[3]),
+        5 => {
+            square(bytes[0], bytes[1]) && square(bytes[2], bytes[3]) && b"qrbn".contains(&bytes[4])
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cozy_chess::Board;
+
+    #[test]
+    fn test_level() {
+        assert!(matches!(
+            Decoder.decode("level 40 5 0"),
+            XBoardInput::Level {
+                moves_per_control: 40,
+                base: 300_000,
+                increment: 0
+            }
+        ));
+        assert!(matches!(
+            Decoder.decode("level 0 2:30 1.5"),
+            XBoardInput::Level {
+                moves_per_control: 0,
+                base: 150_000,
+                increment: 1500
+            }
+        ));
+        assert!(matches!(
+            Decoder.decode("level 0 x 0"),
+            XBoardInput::Invalid { .. }
+        ));
+        assert!(matches!(
+            Decoder.decode("level 40"),
+            XBoardInput::Invalid { .. }
+        ));
+    }
+
+    #[test]
+    fn test_clock_commands() {
+        assert!(matches!(
+            Decoder.decode("time 30000"),
+            XBoardInput::Time(300_000)
+        ));
+        assert!(matches!(
+            Decoder.decode("otim 450"),
+            XBoardInput::OTime(4500)
+        ));
+        assert!(matches!(
+            Decoder.decode("st 10"),
+            XBoardInput::MoveTime(10_000)
+        ));
+        assert!(matches!(Decoder.decode("sd 12"), XBoardInput::Depth(12)));
+        assert!(matches!(Decoder.decode("sd"), XBoardInput::Invalid { .. }));
+    }
+
+    #[test]
+    fn test_moves() {
+        assert!(matches!(
+            Decoder.decode("usermove e2e4"),
+            XBoardInput::UserMove(mv) if mv == "e2e4"
+        ));
+        assert!(matches!(
+            Decoder.decode("e7e8q"),
+            XBoardInput::UserMove(mv) if mv == "e7e8q"
+        ));
+        assert!(matches!(Decoder.decode("e2e9"), XBoardInput::Unknown(_)));
+    }
+
+    #[test]
+    fn test_setboard_and_result() {
+        let XBoardInput::SetBoard(board) =
+            Decoder.decode("setboard rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1")
+        else {
+            panic!("Expected SetBoard")
+        };
+        assert_eq!(board, Board::default());
+
+        assert!(matches!(
+            Decoder.decode("result 1-0 {White mates}"),
+            XBoardInput::Result(result) if result == "1-0 {White mates}"
+        ));
+        assert!(matches!(
+            Decoder.decode("protover 2"),
+            XBoardInput::ProtoVer(2)
+        ));
+        assert!(matches!(Decoder.decode("random"), XBoardInput::Ignored));
+    }
+}
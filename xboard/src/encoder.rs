@@ -0,0 +1,70 @@
+use super::commands::XBoardOutput;
+
+pub struct Encoder {}
+
+impl Encoder {
+    pub fn encode(&self, response: &XBoardOutput) -> String {
+        match response {
+            XBoardOutput::Features(features) => {
+                let features: Vec<String> = features
+                    .iter()
+                    .map(|(name, value)| {
+                        // Numeric values are sent bare, everything else quoted
+                        if value.parse::<i64>().is_ok() {
+                            format!("{}={}", name, value)
+                        } else {
+                            format!("{}=\"{}\"", name, value)
+                        }
+                    })
+                    .collect();
+                format!("feature {}", features.join(" "))
+            }
+            XBoardOutput::Move(mv) => format!("move {}", mv),
+            XBoardOutput::Thinking {
+                depth,
+                score,
+                time,
+                nodes,
+                pv,
+            } => format!("{} {} {} {} {}", depth, score, time, nodes, pv.join(" ")),
+            XBoardOutput::Pong(n) => format!("pong {}", n),
+            XBoardOutput::IllegalMove { mv, reason } => {
+                format!("Illegal move ({}): {}", reason, mv)
+            }
+            XBoardOutput::Error { kind, command } => format!("Error ({}): {}", kind, command),
+            XBoardOutput::Comment(message) => format!("# {}", message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_features() {
+        let features = XBoardOutput::Features(vec![
+            ("ping".to_string(), "1".to_string()),
+            ("myname".to_string(), "Grail 1.0".to_string()),
+            ("done".to_string(), "1".to_string()),
+        ]);
+
+        assert_eq!(
+            Encoder {}.encode(&features),
+            "feature ping=1 myname=\"Grail 1.0\" done=1"
+        );
+    }
+
+    #[test]
+    fn test_encode_thinking() {
+        let thinking = XBoardOutput::Thinking {
+            depth: 9,
+            score: -35,
+            time: 124,
+            nodes: 51234,
+            pv: vec!["e7e5".to_string(), "g1f3".to_string()],
+        };
+
+        assert_eq!(Encoder {}.encode(&thinking), "9 -35 124 51234 e7e5 g1f3");
+    }
+}
@@ -0,0 +1,14 @@
+mod connection;
+mod decoder;
+mod encoder;
+
+pub mod commands;
+
+pub use commands::{XBoardInput, XBoardOutput};
+pub use connection::XBoardConnection;
+pub use decoder::Decoder;
+pub use encoder::Encoder;
+
+/// Score offset for mates in thinking output: mate in N is sent as `MATE_SCORE + N`,
+/// getting mated in N as `-MATE_SCORE - N`.
+pub const MATE_SCORE: i32 = 100000;